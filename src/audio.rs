@@ -1,37 +1,107 @@
+use std::io::{Cursor, Read};
 use std::path::Path;
 
 use anyhow::Result;
-use symphonia::core::audio::SampleBuffer;
-use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::audio::{Channels, SampleBuffer};
+use symphonia::core::codecs::{self, CodecType, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
 use symphonia::core::formats::FormatOptions;
 use symphonia::core::io::MediaSourceStream;
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
 use tracing::{debug, trace, warn};
 
-use crate::errors::AudioError;
+use crate::console::status;
+use crate::errors::{AudioError, OutputError};
+use crate::output::WriteMode;
 
 const WHISPER_SAMPLE_RATE: u32 = 16_000;
 const MIN_AUDIO_SECONDS: f64 = 0.5;
 const MAX_AUDIO_HOURS: f64 = 4.0;
 
-/// Load an audio file, decode to f32 mono, and resample to 16 kHz.
+/// Mono downmix weights for surround layouts: dialog lives in the centre
+/// channel, front L/R at -3 dB, surrounds lower, LFE dropped.
+const CENTRE_WEIGHT: f32 = 1.0;
+const FRONT_WEIGHT: f32 = 0.707;
+const SURROUND_WEIGHT: f32 = 0.5;
+const LFE_WEIGHT: f32 = 0.0;
+
+/// Properties of the source audio as found in the file, before downmix
+/// and resampling.
+#[derive(Clone, Debug)]
+pub struct AudioInfo {
+    /// Codec short name (e.g. `mp3`, `aac`)
+    pub codec: String,
+    /// Source sample rate in Hz
+    pub sample_rate: u32,
+    /// Source channel count
+    pub channels: usize,
+}
+
+/// Input path that means "read the audio from stdin".
+pub const STDIN_PATH: &str = "-";
+
+/// Whether `path` is the `-` stdin placeholder.
+pub fn is_stdin(path: &Path) -> bool {
+    path == Path::new(STDIN_PATH)
+}
+
+/// Load an audio file (or stdin for `-`), decode to f32 mono, and
+/// resample to 16 kHz.
 #[tracing::instrument(skip_all, fields(path = %path.display()))]
-pub fn load_audio(path: &Path) -> Result<Vec<f32>> {
-    // Log file metadata
+pub fn load_audio(path: &Path, input_format: Option<&str>) -> Result<(Vec<f32>, AudioInfo)> {
+    if is_stdin(path) {
+        // Checked before reading so a forgotten flag fails immediately
+        let format = input_format.ok_or(AudioError::StdinFormatRequired)?;
+        let mut bytes = Vec::new();
+        std::io::stdin()
+            .lock()
+            .read_to_end(&mut bytes)
+            .map_err(AudioError::StdinRead)?;
+        debug!(size_bytes = bytes.len(), "Read audio from stdin");
+        if bytes.is_empty() {
+            return Err(AudioError::EmptyFile {
+                path: "<stdin>".to_string(),
+            }
+            .into());
+        }
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(bytes)), Default::default());
+        return decode(mss, Some(format));
+    }
+
+    // Log file metadata; reject empty files before probing
     if let Ok(meta) = std::fs::metadata(path) {
         debug!(size_bytes = meta.len(), "Audio file metadata");
+        if meta.len() == 0 {
+            return Err(AudioError::EmptyFile {
+                path: path.display().to_string(),
+            }
+            .into());
+        }
     }
 
-    let file = std::fs::File::open(path).map_err(|e| AudioError::FileOpen {
-        path: path.display().to_string(),
-        source: e,
+    let file = std::fs::File::open(path).map_err(|e| {
+        if is_locked(&e) {
+            AudioError::FileLocked {
+                path: path.display().to_string(),
+            }
+        } else {
+            AudioError::FileOpen {
+                path: path.display().to_string(),
+                source: e,
+            }
+        }
     })?;
 
     let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    decode(mss, hint_extension(path, input_format))
+}
 
+/// Probe, decode, downmix and resample an opened media stream.
+fn decode(mss: MediaSourceStream, hint_ext: Option<&str>) -> Result<(Vec<f32>, AudioInfo)> {
     let mut hint = Hint::new();
-    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+    if let Some(ext) = hint_ext {
+        debug!(hint = ext, "Format hint");
         hint.with_extension(ext);
     }
 
@@ -56,19 +126,29 @@ pub fn load_audio(path: &Path) -> Result<Vec<f32>> {
     let sample_rate = track.codec_params.sample_rate.unwrap_or(44_100);
     let channels = track.codec_params.channels.map(|c| c.count()).unwrap_or(1);
 
-    debug!(sample_rate, channels, "Detected audio format");
+    let codec = check_codec(track.codec_params.codec)?.to_string();
+
+    debug!(codec, sample_rate, channels, "Detected audio format");
+
+    let info = AudioInfo {
+        codec,
+        sample_rate,
+        channels,
+    };
 
     let mut decoder = symphonia::default::get_codecs()
         .make(&track.codec_params, &DecoderOptions::default())
-        .map_err(|_| AudioError::UnsupportedCodec)?;
+        .map_err(|e| AudioError::DecodeError(format!("invalid {} stream: {e}", info.codec)))?;
 
     let mut pcm: Vec<f32> = Vec::new();
     let mut packet_count: u64 = 0;
+    let mut dropped = DroppedPackets::default();
+    let mut weights: Option<(Channels, Vec<f32>)> = None;
 
     loop {
         let packet = match format.next_packet() {
             Ok(p) => p,
-            Err(symphonia::core::errors::Error::IoError(ref e))
+            Err(SymphoniaError::IoError(ref e))
                 if e.kind() == std::io::ErrorKind::UnexpectedEof =>
             {
                 break;
@@ -82,12 +162,11 @@ pub fn load_audio(path: &Path) -> Result<Vec<f32>> {
             continue;
         }
 
-        let decoded = decoder
-            .decode(&packet)
-            .map_err(|e| AudioError::DecodeError(e.to_string()))?;
+        let Some(decoded) = dropped.record(decoder.decode(&packet), packet.dur, &mut pcm)? else {
+            continue;
+        };
         let spec = *decoded.spec();
         let frames = decoded.frames();
-        let ch = spec.channels.count();
 
         if frames == 0 {
             continue;
@@ -97,17 +176,24 @@ pub fn load_audio(path: &Path) -> Result<Vec<f32>> {
         sbuf.copy_interleaved_ref(decoded);
 
         // Downmix interleaved multi-channel to mono
-        for chunk in sbuf.samples().chunks(ch) {
-            pcm.push(chunk.iter().sum::<f32>() / ch as f32);
+        let layout_changed = weights.as_ref().map(|(c, _)| *c) != Some(spec.channels);
+        if layout_changed {
+            let w = downmix_weights(spec.channels);
+            debug!(channels = ?spec.channels, weights = ?w, "Downmix weights");
+            weights = Some((spec.channels, w));
+        }
+        if let Some((_, w)) = &weights {
+            downmix_into(sbuf.samples(), w, &mut pcm);
         }
 
         packet_count += 1;
-        if packet_count % 500 == 0 {
+        if packet_count.is_multiple_of(500) {
             trace!(packets = packet_count, samples = pcm.len(), "Decoding progress");
         }
     }
 
     debug!(total_packets = packet_count, total_samples = pcm.len(), "Decode complete");
+    dropped.finish()?;
 
     // Resample to 16 kHz if the source rate differs
     if sample_rate != WHISPER_SAMPLE_RATE {
@@ -143,7 +229,247 @@ pub fn load_audio(path: &Path) -> Result<Vec<f32>> {
 
     debug!(duration_secs = format!("{duration_secs:.1}"), samples = pcm.len(), "Audio loaded");
 
-    Ok(pcm)
+    Ok((pcm, info))
+}
+
+/// Whether an open failure means another process holds the file
+/// exclusively (Windows sharing/lock violation).
+fn is_locked(err: &std::io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION (32), ERROR_LOCK_VIOLATION (33)
+    cfg!(windows) && matches!(err.raw_os_error(), Some(32) | Some(33))
+}
+
+/// Largest fraction of packets that may fail to decode before the file
+/// is treated as corrupt rather than "mostly fine".
+const MAX_DROPPED_RATIO: f64 = 0.05;
+
+/// Count of packets skipped because they failed to decode.
+#[derive(Debug, Default)]
+struct DroppedPackets {
+    dropped: u64,
+    total: u64,
+}
+
+impl DroppedPackets {
+    /// Record one packet's decode result. A corrupt packet is logged and
+    /// replaced by `frames` of silence in `pcm` (`Ok(None)`), so later
+    /// timestamps don't shift; any other error aborts decoding.
+    fn record<T>(
+        &mut self,
+        result: symphonia::core::errors::Result<T>,
+        frames: u64,
+        pcm: &mut Vec<f32>,
+    ) -> Result<Option<T>, AudioError> {
+        self.total += 1;
+        match result {
+            Ok(decoded) => Ok(Some(decoded)),
+            Err(SymphoniaError::DecodeError(reason)) => {
+                self.dropped += 1;
+                warn!(packet = self.total, reason, "Skipping corrupt audio packet");
+                pcm.resize(pcm.len() + frames as usize, 0.0);
+                Ok(None)
+            }
+            Err(e) => Err(AudioError::DecodeError(e.to_string())),
+        }
+    }
+
+    /// Report skipped packets; fail if too many were lost.
+    fn finish(&self) -> Result<(), AudioError> {
+        if self.dropped == 0 {
+            return Ok(());
+        }
+        warn!(dropped = self.dropped, total = self.total, "Corrupt packets skipped");
+        status!(
+            "       Warning: skipped {} of {} corrupt audio packets",
+            self.dropped,
+            self.total
+        );
+        if self.dropped as f64 / self.total as f64 > MAX_DROPPED_RATIO {
+            return Err(AudioError::DecodeError(format!(
+                "{} of {} packets could not be decoded",
+                self.dropped, self.total
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Extension used to hint the format probe: the explicit `--input-format`
+/// when given, otherwise the file's own extension.
+fn hint_extension<'a>(path: &'a Path, input_format: Option<&'a str>) -> Option<&'a str> {
+    input_format.or_else(|| path.extension().and_then(|e| e.to_str()))
+}
+
+/// Per-channel mono downmix weights, in interleaved channel order.
+///
+/// Surround layouts (more than two channels including front positions)
+/// use a dialog-centric matrix; mono, stereo and unknown layouts fall back
+/// to equal weights, i.e. plain averaging.
+fn downmix_weights(channels: Channels) -> Vec<f32> {
+    let count = channels.count().max(1);
+    let front = Channels::FRONT_LEFT | Channels::FRONT_RIGHT | Channels::FRONT_CENTRE;
+    if count <= 2 || !channels.intersects(front) {
+        return vec![1.0; count];
+    }
+
+    channels
+        .iter()
+        .map(|ch| {
+            if ch == Channels::FRONT_CENTRE {
+                CENTRE_WEIGHT
+            } else if ch == Channels::LFE1 || ch == Channels::LFE2 {
+                LFE_WEIGHT
+            } else if ch == Channels::FRONT_LEFT
+                || ch == Channels::FRONT_RIGHT
+                || ch == Channels::FRONT_LEFT_CENTRE
+                || ch == Channels::FRONT_RIGHT_CENTRE
+            {
+                FRONT_WEIGHT
+            } else {
+                SURROUND_WEIGHT
+            }
+        })
+        .collect()
+}
+
+/// Mix interleaved frames down to mono with the given per-channel weights,
+/// normalised by the weight sum so the output stays in range.
+fn downmix_into(interleaved: &[f32], weights: &[f32], out: &mut Vec<f32>) {
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 {
+        return;
+    }
+    for frame in interleaved.chunks(weights.len()) {
+        let mixed: f32 = frame.iter().zip(weights).map(|(s, w)| s * w).sum();
+        out.push(mixed / total);
+    }
+}
+
+/// Root-mean-square level of the samples (0.0 for empty input).
+pub fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum_sq: f64 = samples.iter().map(|&s| s as f64 * s as f64).sum();
+    (sum_sq / samples.len() as f64).sqrt() as f32
+}
+
+/// Short name of the decoder for `codec`, or `CodecNotCompiled` when the
+/// container declared a codec this build has no decoder for.
+fn check_codec(codec: CodecType) -> Result<&'static str, AudioError> {
+    symphonia::default::get_codecs()
+        .get_codec(codec)
+        .map(|d| d.short_name)
+        .ok_or_else(|| AudioError::CodecNotCompiled {
+            codec: codec_type_name(codec),
+        })
+}
+
+/// Readable name for codecs Symphonia can identify but not decode.
+fn codec_type_name(codec: CodecType) -> String {
+    let name = match codec {
+        codecs::CODEC_TYPE_OPUS => "opus",
+        codecs::CODEC_TYPE_SPEEX => "speex",
+        codecs::CODEC_TYPE_WAVPACK => "wavpack",
+        codecs::CODEC_TYPE_MUSEPACK => "musepack",
+        codecs::CODEC_TYPE_AAC => "aac",
+        codecs::CODEC_TYPE_ALAC => "alac",
+        codecs::CODEC_TYPE_VORBIS => "vorbis",
+        _ => return format!("{codec:?}"),
+    };
+    name.to_string()
+}
+
+/// Absolute level at or above which a sample counts as clipped.
+const CLIP_LEVEL: f32 = 0.999;
+
+/// Signal-level measurements of decoded audio (`--stats-only`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignalStats {
+    pub duration_secs: f64,
+    pub rms: f32,
+    pub peak: f32,
+    /// Fraction of samples at or above full scale
+    pub clipping_ratio: f64,
+}
+
+impl SignalStats {
+    /// Measure 16 kHz mono samples as returned by [`load_audio`].
+    pub fn measure(samples: &[f32]) -> Self {
+        let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        let clipped = samples.iter().filter(|s| s.abs() >= CLIP_LEVEL).count();
+        Self {
+            duration_secs: samples.len() as f64 / 16_000.0,
+            rms: rms(samples),
+            peak,
+            clipping_ratio: if samples.is_empty() {
+                0.0
+            } else {
+                clipped as f64 / samples.len() as f64
+            },
+        }
+    }
+
+    /// Human-readable report alongside the source format details.
+    pub fn summary(&self, info: &AudioInfo) -> String {
+        format!(
+            "Format: {}, {} Hz, {} ch\nDuration: {:.1}s\nRMS: {:.4} ({:.1} dBFS)\nPeak: {:.4} ({:.1} dBFS)\nClipping: {:.3}% of samples\n",
+            info.codec,
+            info.sample_rate,
+            info.channels,
+            self.duration_secs,
+            self.rms,
+            dbfs(self.rms),
+            self.peak,
+            dbfs(self.peak),
+            self.clipping_ratio * 100.0,
+        )
+    }
+}
+
+/// Write 16 kHz mono `samples` to `path` as a 32-bit float WAV file,
+/// honouring `mode` like the transcript outputs: `Append` adds the samples
+/// to an existing dump.
+pub fn write_wav(path: &Path, samples: &[f32], mode: WriteMode) -> Result<(), OutputError> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: WHISPER_SAMPLE_RATE,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let failed = |e: hound::Error| OutputError::WriteFailed(format!("{}: {e}", path.display()));
+    let mut writer = match mode {
+        WriteMode::Append if path.exists() => hound::WavWriter::append(path).map_err(failed)?,
+        WriteMode::Protect => {
+            let file = std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(path)
+                .map_err(|e| {
+                    if e.kind() == std::io::ErrorKind::AlreadyExists {
+                        OutputError::AlreadyExists {
+                            path: path.display().to_string(),
+                        }
+                    } else {
+                        OutputError::FileCreate {
+                            path: path.display().to_string(),
+                            source: e,
+                        }
+                    }
+                })?;
+            hound::WavWriter::new(std::io::BufWriter::new(file), spec).map_err(failed)?
+        }
+        _ => hound::WavWriter::create(path, spec).map_err(failed)?,
+    };
+    for &sample in samples {
+        writer.write_sample(sample).map_err(failed)?;
+    }
+    writer.finalize().map_err(failed)
+}
+
+/// Level in decibels relative to full scale (`-inf` for silence).
+fn dbfs(level: f32) -> f32 {
+    20.0 * level.log10()
 }
 
 /// Linear-interpolation resampler (adequate for speech recognition).
@@ -174,3 +500,211 @@ fn resample(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
 
     output
 }
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::ScratchDir;
+
+    /// 5.1 layout in Symphonia's interleaved order: FL FR FC LFE RL RR.
+    fn layout_5_1() -> Channels {
+        Channels::FRONT_LEFT
+            | Channels::FRONT_RIGHT
+            | Channels::FRONT_CENTRE
+            | Channels::LFE1
+            | Channels::REAR_LEFT
+            | Channels::REAR_RIGHT
+    }
+
+    fn mix(frame: [f32; 6]) -> f32 {
+        let mut out = Vec::new();
+        downmix_into(&frame, &downmix_weights(layout_5_1()), &mut out);
+        out[0]
+    }
+
+    #[test]
+    fn zero_byte_file_rejected_before_probing() {
+        let dir = ScratchDir::new("empty-audio");
+        let path = dir.join("empty.mp3");
+        std::fs::write(&path, b"").unwrap();
+
+        let err = load_audio(&path, None).expect_err("empty file must fail");
+
+        assert!(matches!(
+            err.downcast_ref::<AudioError>(),
+            Some(AudioError::EmptyFile { .. })
+        ));
+    }
+
+    #[test]
+    fn hint_prefers_input_format_override() {
+        let path = Path::new("recording.dat");
+        assert_eq!(hint_extension(path, Some("wav")), Some("wav"));
+        assert_eq!(hint_extension(path, None), Some("dat"));
+        assert_eq!(hint_extension(Path::new("recording"), None), None);
+    }
+
+    #[test]
+    fn surround_weights_favour_dialog() {
+        let w = downmix_weights(layout_5_1());
+        assert_eq!(w, vec![0.707, 0.707, 1.0, 0.0, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn centre_dominates_surround_and_lfe_is_dropped() {
+        let centre = mix([0.0, 0.0, 1.0, 0.0, 0.0, 0.0]);
+        let rear = mix([0.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+        let lfe = mix([0.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+        assert!((centre / rear - 2.0).abs() < 1e-4);
+        assert_eq!(lfe, 0.0);
+        // Full-scale on every channel stays in range
+        assert!(mix([1.0; 6]) <= 1.0);
+    }
+
+    #[test]
+    fn stereo_falls_back_to_plain_average() {
+        let stereo = Channels::FRONT_LEFT | Channels::FRONT_RIGHT;
+        let mut out = Vec::new();
+        downmix_into(&[1.0, 0.0, 0.2, 0.4], &downmix_weights(stereo), &mut out);
+        assert_eq!(out.len(), 2);
+        assert!((out[0] - 0.5).abs() < 1e-6);
+        assert!((out[1] - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn signal_stats_of_known_buffer() {
+        // Square wave at ±0.5 with two full-scale samples
+        let mut samples: Vec<f32> = (0..16_000)
+            .map(|i| if i % 2 == 0 { 0.5 } else { -0.5 })
+            .collect();
+        samples[0] = 1.0;
+        samples[1] = -1.0;
+        let stats = SignalStats::measure(&samples);
+        assert_eq!(stats.duration_secs, 1.0);
+        assert_eq!(stats.peak, 1.0);
+        assert!((stats.clipping_ratio - 2.0 / 16_000.0).abs() < 1e-12);
+        let expected_rms = ((15_998.0 * 0.25 + 2.0) / 16_000.0f64).sqrt() as f32;
+        assert!((stats.rms - expected_rms).abs() < 1e-6);
+    }
+
+    #[test]
+    fn signal_stats_of_silence() {
+        let stats = SignalStats::measure(&[0.0; 1_600]);
+        assert_eq!((stats.rms, stats.peak, stats.clipping_ratio), (0.0, 0.0, 0.0));
+        assert!(stats.summary(&AudioInfo {
+            codec: "pcm_s16le".to_string(),
+            sample_rate: 16_000,
+            channels: 1,
+        })
+        .contains("RMS: 0.0000 (-inf dBFS)"));
+    }
+
+    #[test]
+    fn dumped_wav_matches_whisper_input() {
+        let dir = ScratchDir::new("dump-audio");
+        let path = dir.join("dump.wav");
+        let samples: Vec<f32> = (0..24_000).map(|i| (i as f32 * 0.01).sin() * 0.5).collect();
+        write_wav(&path, &samples, WriteMode::Protect).unwrap();
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        let spec = reader.spec();
+        let dumped: Vec<f32> = reader.into_samples().map(|s| s.unwrap()).collect();
+
+        assert_eq!((spec.sample_rate, spec.channels), (16_000, 1));
+        assert_eq!(dumped.len(), samples.len());
+        assert_eq!(dumped, samples);
+    }
+
+    #[test]
+    fn dumped_wav_honours_write_mode() {
+        let dir = ScratchDir::new("dump-audio-mode");
+        let path = dir.join("dump.wav");
+        write_wav(&path, &[0.25; 100], WriteMode::Protect).unwrap();
+
+        let err = write_wav(&path, &[0.5; 10], WriteMode::Protect).unwrap_err();
+        assert!(matches!(err, OutputError::AlreadyExists { .. }));
+
+        write_wav(&path, &[0.5; 10], WriteMode::Append).unwrap();
+        assert_eq!(hound::WavReader::open(&path).unwrap().len(), 110);
+
+        write_wav(&path, &[0.5; 10], WriteMode::Overwrite).unwrap();
+        assert_eq!(hound::WavReader::open(&path).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn undecodable_codec_reported_by_name() {
+        let err = check_codec(codecs::CODEC_TYPE_OPUS).unwrap_err();
+        assert!(matches!(&err, AudioError::CodecNotCompiled { codec } if codec == "opus"));
+        assert!(err.to_string().contains("'opus' was recognized"));
+        assert_eq!(check_codec(codecs::CODEC_TYPE_MP3).unwrap(), "mp3");
+    }
+
+    #[test]
+    fn stdin_requires_input_format() {
+        assert!(is_stdin(Path::new("-")));
+        assert!(!is_stdin(Path::new("./-.mp3")));
+
+        let err =
+            load_audio(Path::new("-"), None).expect_err("stdin without --input-format must fail");
+        let audio_err = err.downcast_ref::<AudioError>().unwrap();
+        assert!(matches!(audio_err, AudioError::StdinFormatRequired));
+        assert!(err.to_string().contains("--input-format"));
+    }
+
+    /// Frames per packet from the mock decoder.
+    const MOCK_FRAMES: u64 = 4;
+
+    /// Feed `packets` results from a mock decoder through the tracker;
+    /// a decoded packet contributes `MOCK_FRAMES` samples of 1.0.
+    fn decode_mock(
+        packets: u64,
+        fails: impl Fn(u64) -> bool,
+    ) -> Result<(Vec<f32>, DroppedPackets), AudioError> {
+        let mut dropped = DroppedPackets::default();
+        let mut pcm = Vec::new();
+        for i in 0..packets {
+            let result = if fails(i) {
+                Err(SymphoniaError::DecodeError("invalid main_data offset"))
+            } else {
+                Ok(i)
+            };
+            if dropped.record(result, MOCK_FRAMES, &mut pcm)?.is_some() {
+                pcm.resize(pcm.len() + MOCK_FRAMES as usize, 1.0);
+            }
+        }
+        dropped.finish()?;
+        Ok((pcm, dropped))
+    }
+
+    #[test]
+    fn corrupt_packets_replaced_by_silence() {
+        let (pcm, dropped) = decode_mock(100, |i| i == 3 || i == 57).unwrap();
+        assert_eq!(pcm.len(), 100 * MOCK_FRAMES as usize, "timing kept");
+        let n = MOCK_FRAMES as usize;
+        let frames = |packet: usize| &pcm[packet * n..(packet + 1) * n];
+        assert!(frames(3).iter().chain(frames(57)).all(|&s| s == 0.0));
+        assert!(frames(4).iter().all(|&s| s == 1.0));
+        assert_eq!(pcm.iter().filter(|&&s| s == 0.0).count(), 2 * n);
+        assert_eq!((dropped.dropped, dropped.total), (2, 100));
+    }
+
+    #[test]
+    fn too_many_corrupt_packets_abort() {
+        let err = decode_mock(100, |i| i % 2 == 0).unwrap_err();
+        assert!(matches!(
+            err,
+            AudioError::DecodeError(ref m) if m == "50 of 100 packets could not be decoded"
+        ));
+    }
+
+    #[test]
+    fn non_decode_errors_still_fatal() {
+        let mut dropped = DroppedPackets::default();
+        let io = std::io::Error::other("disk");
+        let result: symphonia::core::errors::Result<()> = Err(SymphoniaError::IoError(io));
+        let mut pcm = Vec::new();
+        assert!(dropped.record(result, MOCK_FRAMES, &mut pcm).is_err());
+    }
+}
@@ -0,0 +1,393 @@
+//! Directory batch mode: transcribe every audio file in a folder.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::errors::{AudioError, OutputError};
+use crate::output::{self, Encoding, Target, WriteMode};
+use crate::transcribe::Metrics;
+
+/// File extensions treated as audio when scanning a directory.
+pub static AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "m4a", "aac", "ogg", "flac", "wma"];
+
+/// Audio files directly inside `dir` (not recursive), sorted by name.
+pub fn collect_inputs(dir: &Path) -> Result<Vec<PathBuf>, AudioError> {
+    let entries = std::fs::read_dir(dir).map_err(|e| AudioError::FileOpen {
+        path: dir.display().to_string(),
+        source: e,
+    })?;
+
+    let mut inputs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && is_audio_file(p))
+        .collect();
+    inputs.sort();
+    Ok(inputs)
+}
+
+fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| AUDIO_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Default output path: `<stem>_transcript.<ext>` next to the audio file,
+/// or inside `out_dir` when given.
+pub fn output_path(audio: &Path, out_dir: Option<&Path>, format: &str) -> PathBuf {
+    let stem = audio.file_stem().unwrap_or_default();
+    let parent = out_dir
+        .or_else(|| audio.parent())
+        .unwrap_or_else(|| Path::new("."));
+    let ext = output::extension(format);
+    parent.join(format!("{}_transcript.{ext}", stem.to_string_lossy()))
+}
+
+/// One default output target per format (see [`output_path`]).
+pub fn output_targets(audio: &Path, out_dir: Option<&Path>, formats: &[String]) -> Vec<Target> {
+    formats
+        .iter()
+        .map(|format| Target {
+            format: format.clone(),
+            path: output_path(audio, out_dir, format),
+        })
+        .collect()
+}
+
+/// Whether `output` exists and was modified after `input`, i.e. the
+/// transcript is newer than the audio it came from.
+pub fn is_up_to_date(input: &Path, output: &Path) -> bool {
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (modified(input), modified(output)) {
+        (Some(i), Some(o)) => o > i,
+        _ => false,
+    }
+}
+
+/// Write mode for `input` under `--skip-existing`: `None` when every
+/// target is up to date and the file is skipped. Stale transcripts are
+/// replaced, since protecting or appending to them would fail the file or
+/// duplicate the text.
+pub fn resume_mode(input: &Path, targets: &[Target], mode: WriteMode) -> Option<WriteMode> {
+    if !targets.iter().any(|t| t.path.exists()) {
+        return Some(mode);
+    }
+    if targets.iter().all(|t| is_up_to_date(input, &t.path)) {
+        return None;
+    }
+    Some(WriteMode::Overwrite)
+}
+
+/// Run `work` on every input using up to `jobs` worker threads. Workers
+/// pull the next unclaimed input until none are left; results are
+/// returned in input order.
+pub fn run_parallel<T, R, F>(inputs: &[T], jobs: usize, work: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(usize, &T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new((0..inputs.len()).map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, inputs.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(input) = inputs.get(i) else {
+                    break;
+                };
+                let result = work(i, input);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.expect("every input is processed"))
+        .collect()
+}
+
+// ── Batch index ─────────────────────────────────────────────────────
+
+/// Outcome of one file in a batch, as recorded in the `--index` file.
+#[derive(Debug, Serialize)]
+pub struct IndexEntry {
+    pub source: String,
+    /// Output paths, `;`-separated when several formats were written
+    pub output: String,
+    /// `ok`, `skipped` or `failed`
+    pub status: &'static str,
+    #[serde(flatten)]
+    pub metrics: Option<Metrics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Write the batch index: CSV when `path` ends in `.csv`, JSON otherwise.
+/// An existing index is protected or replaced per `mode`; it is never
+/// appended to, which would leave invalid JSON or a second CSV header.
+pub fn write_index(
+    path: &Path,
+    entries: &[IndexEntry],
+    mode: WriteMode,
+) -> Result<(), OutputError> {
+    let is_csv = path
+        .extension()
+        .map(|e| e.eq_ignore_ascii_case("csv"))
+        .unwrap_or(false);
+    let contents = if is_csv {
+        render_index_csv(entries)
+    } else {
+        render_index_json(entries)?
+    };
+    let mode = match mode {
+        WriteMode::Append => WriteMode::Overwrite,
+        mode => mode,
+    };
+    output::write_file(path, &contents, mode, Encoding::Utf8)
+}
+
+fn render_index_json(entries: &[IndexEntry]) -> Result<String, OutputError> {
+    let mut json =
+        serde_json::to_string_pretty(entries).map_err(|e| OutputError::WriteFailed(e.to_string()))?;
+    json.push('\n');
+    Ok(json)
+}
+
+fn render_index_csv(entries: &[IndexEntry]) -> String {
+    let mut out = String::from(
+        "source,output,status,audio_duration_secs,transcribe_secs,realtime_factor,segments,error\n",
+    );
+    for e in entries {
+        let (duration, transcribe, rtf, segments) = match &e.metrics {
+            Some(m) => (
+                format!("{:.1}", m.audio_duration_secs),
+                format!("{:.1}", m.transcribe_secs),
+                format!("{:.2}", m.realtime_factor),
+                m.segments.to_string(),
+            ),
+            None => Default::default(),
+        };
+        let _ = writeln!(
+            out,
+            "{},{},{},{duration},{transcribe},{rtf},{segments},{}",
+            csv_field(&e.source),
+            csv_field(&e.output),
+            e.status,
+            csv_field(e.error.as_deref().unwrap_or("")),
+        );
+    }
+    out
+}
+
+/// Quote a CSV field if it contains a separator, quote or newline.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::ScratchDir;
+    use std::time::{Duration, SystemTime};
+
+    fn touch(path: &Path, mtime: SystemTime) {
+        let f = std::fs::File::create(path).unwrap();
+        f.set_modified(mtime).unwrap();
+    }
+
+    #[test]
+    fn up_to_date_when_output_newer() {
+        let dir = ScratchDir::new("fresh");
+        let now = SystemTime::now();
+        let (input, output) = (dir.join("a.mp3"), dir.join("a_transcript.txt"));
+        touch(&input, now - Duration::from_secs(60));
+        touch(&output, now);
+        assert!(is_up_to_date(&input, &output));
+    }
+
+    #[test]
+    fn stale_or_missing_output_is_reprocessed() {
+        let dir = ScratchDir::new("stale");
+        let now = SystemTime::now();
+        let (input, output) = (dir.join("a.mp3"), dir.join("a_transcript.txt"));
+        touch(&output, now - Duration::from_secs(60));
+        touch(&input, now);
+        assert!(!is_up_to_date(&input, &output), "audio changed after transcript");
+        assert!(!is_up_to_date(&input, &dir.join("missing.txt")));
+    }
+
+    #[test]
+    fn stale_outputs_replaced_whatever_the_write_mode() {
+        let dir = ScratchDir::new("resume");
+        let now = SystemTime::now();
+        let input = dir.join("a.mp3");
+        let targets = output_targets(&input, None, &["txt".to_string(), "srt".to_string()]);
+        touch(&input, now - Duration::from_secs(60));
+
+        // Nothing written yet: the chosen mode applies
+        assert_eq!(
+            resume_mode(&input, &targets, WriteMode::Protect),
+            Some(WriteMode::Protect)
+        );
+
+        touch(&targets[0].path, now);
+        touch(&targets[1].path, now);
+        assert_eq!(resume_mode(&input, &targets, WriteMode::Append), None);
+
+        // The audio changed after one of the transcripts
+        touch(&targets[1].path, now - Duration::from_secs(120));
+        for mode in [WriteMode::Protect, WriteMode::Append] {
+            assert_eq!(
+                resume_mode(&input, &targets, mode),
+                Some(WriteMode::Overwrite)
+            );
+        }
+    }
+
+    #[test]
+    fn collects_only_audio_files_sorted() {
+        let dir = ScratchDir::new("collect");
+        for name in ["b.MP3", "a.wav", "notes.txt", "c.flac"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        let names: Vec<_> = collect_inputs(&dir)
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["a.wav", "b.MP3", "c.flac"]);
+    }
+
+    #[test]
+    fn every_input_dispatched_once() {
+        use std::collections::HashSet;
+        use std::thread::ThreadId;
+
+        let inputs: Vec<usize> = (0..4).collect();
+        let threads = Mutex::new(HashSet::<ThreadId>::new());
+        let barrier = std::sync::Barrier::new(4);
+        let results = run_parallel(&inputs, 4, |i, &input| {
+            threads.lock().unwrap().insert(std::thread::current().id());
+            // Returns only once all four inputs are in flight at once
+            barrier.wait();
+            (i, input * 10)
+        });
+
+        assert_eq!(results, vec![(0, 0), (1, 10), (2, 20), (3, 30)]);
+        assert_eq!(threads.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn more_inputs_than_workers_all_processed() {
+        let inputs: Vec<u32> = (0..10).collect();
+        let calls = AtomicUsize::new(0);
+        let results = run_parallel(&inputs, 3, |_, &n| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            n * n
+        });
+        assert_eq!(calls.into_inner(), 10);
+        assert_eq!(results, inputs.iter().map(|n| n * n).collect::<Vec<_>>());
+        assert!(run_parallel(&[] as &[u32], 2, |_, &n| n).is_empty());
+    }
+
+    fn mixed_index() -> Vec<IndexEntry> {
+        vec![
+            IndexEntry {
+                source: "a.mp3".to_string(),
+                output: "a_transcript.txt".to_string(),
+                status: "ok",
+                metrics: Some(Metrics {
+                    audio_duration_secs: 60.0,
+                    transcribe_secs: 15.0,
+                    realtime_factor: 0.25,
+                    segments: 12,
+                    stages: Default::default(),
+                }),
+                error: None,
+            },
+            IndexEntry {
+                source: "b.mp3".to_string(),
+                output: "b_transcript.txt".to_string(),
+                status: "failed",
+                metrics: None,
+                error: Some("Unsupported audio format, \"b.mp3\"".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn index_json_includes_successes_and_failures() {
+        let json = render_index_json(&mixed_index()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let entries = value.as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0]["status"], "ok");
+        assert_eq!(entries[0]["segments"], 12);
+        assert_eq!(entries[0]["realtime_factor"], 0.25);
+        assert!(entries[0].get("error").is_none());
+
+        assert_eq!(entries[1]["status"], "failed");
+        assert_eq!(entries[1]["error"], "Unsupported audio format, \"b.mp3\"");
+        assert!(entries[1].get("segments").is_none());
+    }
+
+    #[test]
+    fn index_csv_quotes_error_messages() {
+        let csv = render_index_csv(&mixed_index());
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "a.mp3,a_transcript.txt,ok,60.0,15.0,0.25,12,");
+        assert_eq!(
+            lines[2],
+            "b.mp3,b_transcript.txt,failed,,,,,\"Unsupported audio format, \"\"b.mp3\"\"\""
+        );
+    }
+
+    #[test]
+    fn index_honours_write_mode() {
+        let dir = ScratchDir::new("index");
+        let path = dir.join("index.csv");
+        std::fs::write(&path, "eski\n").unwrap();
+
+        let err = write_index(&path, &mixed_index(), WriteMode::Protect).unwrap_err();
+        assert!(matches!(err, OutputError::AlreadyExists { .. }));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "eski\n");
+
+        write_index(&path, &mixed_index(), WriteMode::Overwrite).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert!(csv.starts_with("source,output,status"));
+
+        // A second run under --append replaces the index
+        write_index(&path, &mixed_index(), WriteMode::Append).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), csv);
+    }
+
+    #[test]
+    fn output_path_derived_from_stem() {
+        let audio = Path::new("/kayitlar/toplanti.mp3");
+        assert_eq!(
+            output_path(audio, None, "plain"),
+            Path::new("/kayitlar/toplanti_transcript.txt")
+        );
+        assert_eq!(
+            output_path(audio, Some(Path::new("/cikti")), "srt"),
+            Path::new("/cikti/toplanti_transcript.srt")
+        );
+    }
+}
@@ -0,0 +1,326 @@
+//! Mid-file checkpoints for `--checkpoint`.
+//!
+//! The audio is transcribed one chunk at a time and every finished chunk's
+//! segments are saved to a `.partial` sidecar next to the output. A
+//! restarted run whose sidecar matches the input (by SHA-256), model,
+//! decode settings and audio length resumes from the first chunk that
+//! isn't done. The sidecar is removed once the output has been written.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::errors::OutputError;
+use crate::model;
+use crate::output;
+use crate::transcribe::Segment;
+
+/// Suffix appended to the output path for the sidecar.
+const SIDECAR_SUFFIX: &str = ".partial";
+
+/// Progress of one transcription, as saved in the sidecar.
+#[derive(Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// SHA-256 of the input audio file
+    pub audio_sha256: String,
+    pub model: String,
+    /// Decode settings the chunks were transcribed with
+    pub settings: String,
+    /// Number of samples transcribed (after `--vad`, if enabled)
+    pub total_samples: usize,
+    /// Chunk length in samples
+    pub chunk_len: usize,
+    /// Finished chunks, in completion order
+    pub chunks: Vec<ChunkResult>,
+}
+
+/// Segments of one finished chunk, already on the full timeline.
+#[derive(Serialize, Deserialize)]
+pub struct ChunkResult {
+    pub index: usize,
+    pub segments: Vec<Segment>,
+}
+
+/// Sidecar path for a transcription: next to the first file target, or
+/// next to the audio when everything goes to stdout.
+pub fn sidecar_path(targets: &[output::Target], audio_path: &Path) -> PathBuf {
+    let base = targets
+        .iter()
+        .map(|t| t.path.as_path())
+        .find(|p| *p != Path::new(output::STDOUT_PATH))
+        .unwrap_or(audio_path);
+    let mut path = base.as_os_str().to_owned();
+    path.push(SIDECAR_SUFFIX);
+    PathBuf::from(path)
+}
+
+impl Checkpoint {
+    pub fn new(
+        audio_sha256: String,
+        model: &str,
+        settings: &str,
+        total_samples: usize,
+        chunk_len: usize,
+    ) -> Self {
+        Self {
+            audio_sha256,
+            model: model.to_string(),
+            settings: settings.to_string(),
+            total_samples,
+            chunk_len,
+            chunks: Vec::new(),
+        }
+    }
+
+    /// Number of chunks the audio is split into.
+    pub fn total_chunks(&self) -> usize {
+        self.total_samples.div_ceil(self.chunk_len.max(1))
+    }
+
+    /// Index of the first chunk that isn't done (`total_chunks` when all are).
+    pub fn resume_point(&self) -> usize {
+        (0..self.total_chunks())
+            .find(|i| !self.chunks.iter().any(|c| c.index == *i))
+            .unwrap_or(self.total_chunks())
+    }
+
+    /// Record `segments` as the result of chunk `index`, replacing any
+    /// earlier result for it.
+    pub fn record(&mut self, index: usize, segments: Vec<Segment>) {
+        self.chunks.retain(|c| c.index != index);
+        self.chunks.push(ChunkResult { index, segments });
+    }
+
+    /// Text of chunk `index`, if it is done.
+    pub fn chunk_text(&self, index: usize) -> Option<String> {
+        let chunk = self.chunks.iter().find(|c| c.index == index)?;
+        let texts: Vec<&str> = chunk.segments.iter().map(|s| s.text.as_str()).collect();
+        Some(texts.join(" "))
+    }
+
+    /// All recorded segments in chunk order.
+    pub fn into_segments(mut self) -> Vec<Segment> {
+        self.chunks.sort_by_key(|c| c.index);
+        self.chunks.into_iter().flat_map(|c| c.segments).collect()
+    }
+
+    /// Whether `other` was made for the same input, model, settings and
+    /// audio length.
+    fn matches(&self, other: &Checkpoint) -> bool {
+        self.audio_sha256 == other.audio_sha256
+            && self.model == other.model
+            && self.settings == other.settings
+            && self.total_samples == other.total_samples
+            && self.chunk_len == other.chunk_len
+    }
+
+    /// Read a sidecar; `None` if it is missing or unreadable.
+    pub fn load(path: &Path) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        match serde_json::from_str(&contents) {
+            Ok(checkpoint) => Some(checkpoint),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Ignoring unreadable checkpoint");
+                None
+            }
+        }
+    }
+
+    /// Write the sidecar through a temporary file, so a crash mid-write
+    /// leaves the previous checkpoint intact.
+    pub fn save(&self, path: &Path) -> Result<(), OutputError> {
+        let write_failed = |e: std::io::Error| OutputError::CheckpointWrite {
+            path: path.display().to_string(),
+            source: e,
+        };
+        let json = serde_json::to_string(self)
+            .map_err(|e| OutputError::WriteFailed(format!("JSON serialization failed: {e}")))?;
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, json).map_err(write_failed)?;
+        std::fs::rename(&tmp, path).map_err(write_failed)
+    }
+}
+
+/// Start or resume a checkpointed transcription of `audio_path`. An
+/// existing sidecar is reused only if it matches the current input;
+/// returns `None` (no checkpointing) if the input can't be hashed.
+pub fn start(
+    sidecar: &Path,
+    audio_path: &Path,
+    model: &str,
+    settings: &str,
+    total_samples: usize,
+    chunk_len: usize,
+) -> Option<Checkpoint> {
+    let hash = match model::sha256_hex(audio_path) {
+        Ok(hash) => hash,
+        Err(e) => {
+            warn!(error = %e, "Cannot hash input — checkpointing disabled");
+            return None;
+        }
+    };
+    let fresh = Checkpoint::new(hash, model, settings, total_samples, chunk_len);
+
+    match Checkpoint::load(sidecar) {
+        Some(saved) if saved.matches(&fresh) => {
+            info!(
+                path = %sidecar.display(),
+                done = saved.resume_point(),
+                total = saved.total_chunks(),
+                "Resuming from checkpoint"
+            );
+            Some(saved)
+        }
+        Some(_) => {
+            warn!(
+                path = %sidecar.display(),
+                "Checkpoint is for a different input, model or settings — starting over"
+            );
+            Some(fresh)
+        }
+        None => Some(fresh),
+    }
+}
+
+/// Remove the sidecar after the output has been written.
+pub fn finish(sidecar: &Path) {
+    match std::fs::remove_file(sidecar) {
+        Ok(()) => info!(path = %sidecar.display(), "Checkpoint removed"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => warn!(path = %sidecar.display(), error = %e, "Cannot remove checkpoint"),
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::ScratchDir;
+
+    fn seg(start: f64, text: &str) -> Segment {
+        Segment {
+            start,
+            end: start + 1.0,
+            text: text.to_string(),
+            confidence: Some(0.9),
+            ..Segment::default()
+        }
+    }
+
+    #[test]
+    fn sidecar_round_trips() {
+        let dir = ScratchDir::new("checkpoint-roundtrip");
+        let path = dir.join("kayit_transcript.txt.partial");
+        let mut checkpoint = Checkpoint::new("ab12".to_string(), "large-v3", "beam=5", 100, 30);
+        checkpoint.record(0, vec![seg(0.0, "Merhaba."), seg(1.0, "Hoş geldiniz.")]);
+        checkpoint.record(1, vec![seg(31.0, "Gündem şöyle:")]);
+        checkpoint.save(&path).unwrap();
+
+        let loaded = Checkpoint::load(&path).unwrap();
+        assert!(loaded.matches(&checkpoint));
+        assert_eq!(loaded.resume_point(), 2);
+        assert_eq!(
+            loaded.chunk_text(0).as_deref(),
+            Some("Merhaba. Hoş geldiniz.")
+        );
+        assert_eq!(loaded.chunk_text(2), None);
+        let segments = loaded.into_segments();
+        let texts: Vec<&str> = segments.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["Merhaba.", "Hoş geldiniz.", "Gündem şöyle:"]);
+        assert_eq!(
+            (segments[2].start, segments[2].confidence),
+            (31.0, Some(0.9))
+        );
+
+        std::fs::write(&path, "{ yarım").unwrap();
+        assert!(
+            Checkpoint::load(&path).is_none(),
+            "truncated sidecar ignored"
+        );
+    }
+
+    #[test]
+    fn resume_point_is_first_missing_chunk() {
+        let mut checkpoint = Checkpoint::new(String::new(), "medium", "", 100, 30);
+        assert_eq!(checkpoint.total_chunks(), 4);
+        assert_eq!(checkpoint.resume_point(), 0);
+
+        checkpoint.record(0, Vec::new());
+        checkpoint.record(1, Vec::new());
+        checkpoint.record(3, Vec::new());
+        assert_eq!(checkpoint.resume_point(), 2);
+
+        checkpoint.record(2, Vec::new());
+        checkpoint.record(2, vec![seg(60.0, "tekrar")]);
+        assert_eq!(checkpoint.chunks.len(), 4, "re-recorded chunk replaced");
+        assert_eq!(checkpoint.resume_point(), 4);
+    }
+
+    #[test]
+    fn sidecar_for_other_input_not_resumed() {
+        let dir = ScratchDir::new("checkpoint-mismatch");
+        let audio = dir.join("kayit.wav");
+        let sidecar = dir.join("kayit_transcript.txt.partial");
+        std::fs::write(&audio, b"ilk kayit").unwrap();
+
+        let mut checkpoint = start(&sidecar, &audio, "small", "beam=5", 100, 30).unwrap();
+        checkpoint.record(0, vec![seg(0.0, "eski")]);
+        checkpoint.save(&sidecar).unwrap();
+        assert_eq!(
+            start(&sidecar, &audio, "small", "beam=5", 100, 30)
+                .unwrap()
+                .resume_point(),
+            1
+        );
+
+        // Same sidecar, different model, settings or audio: start over
+        assert_eq!(
+            start(&sidecar, &audio, "medium", "beam=5", 100, 30)
+                .unwrap()
+                .resume_point(),
+            0
+        );
+        assert_eq!(
+            start(&sidecar, &audio, "small", "beam=1", 100, 30)
+                .unwrap()
+                .resume_point(),
+            0
+        );
+        std::fs::write(&audio, b"ikinci kayit").unwrap();
+        assert_eq!(
+            start(&sidecar, &audio, "small", "beam=5", 100, 30)
+                .unwrap()
+                .resume_point(),
+            0
+        );
+
+        finish(&sidecar);
+        assert!(!sidecar.exists());
+    }
+
+    #[test]
+    fn sidecar_sits_next_to_first_file_output() {
+        let audio = Path::new("kayitlar/kayit.mp3");
+        let targets = [
+            output::Target {
+                format: "txt".to_string(),
+                path: PathBuf::from(output::STDOUT_PATH),
+            },
+            output::Target {
+                format: "srt".to_string(),
+                path: PathBuf::from("out/kayit_transcript.srt"),
+            },
+        ];
+        assert_eq!(
+            sidecar_path(&targets, audio),
+            Path::new("out/kayit_transcript.srt.partial")
+        );
+        assert_eq!(
+            sidecar_path(&targets[..1], audio),
+            Path::new("kayitlar/kayit.mp3.partial")
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Fixed-length chunking of 16 kHz audio and per-chunk language runs.
+//!
+//! `--multilingual` detects the language of every chunk, merges adjacent
+//! chunks with the same language into runs, and transcribes each run on
+//! its own. Run boundaries are moved to the quietest nearby point so a
+//! language switch doesn't cut a word in half.
+
+use std::ops::Range;
+
+use crate::transcribe::Segment;
+
+/// Sample rate of decoded audio.
+const SAMPLE_RATE: usize = 16_000;
+
+/// Chunk length in seconds — one Whisper window.
+pub const CHUNK_SECS: usize = 30;
+
+/// How far (in seconds) a run boundary may move to find silence.
+const BOUNDARY_SEARCH_SECS: f64 = 1.0;
+
+/// Frame length used when searching for silence (20 ms).
+const FRAME: usize = SAMPLE_RATE / 50;
+
+/// A contiguous stretch of audio transcribed with one language.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageRun {
+    pub range: Range<usize>,
+    pub language: &'static str,
+}
+
+/// Split `total` samples into consecutive ranges of `chunk_len` samples;
+/// the last range holds the remainder.
+pub fn chunk_ranges(total: usize, chunk_len: usize) -> Vec<Range<usize>> {
+    (0..total)
+        .step_by(chunk_len.max(1))
+        .map(|start| start..(start + chunk_len).min(total))
+        .collect()
+}
+
+/// Like [`chunk_ranges`], but with each boundary moved to the quietest
+/// frame within `BOUNDARY_SEARCH_SECS` of the chunk edge, so a chunk
+/// doesn't end mid-word.
+pub fn quiet_chunk_ranges(samples: &[f32], chunk_len: usize) -> Vec<Range<usize>> {
+    let mut ranges = chunk_ranges(samples.len(), chunk_len);
+    for i in 1..ranges.len() {
+        let cut = boundary_cut(samples, ranges[i - 1].start, ranges[i].start, ranges[i].end);
+        ranges[i - 1].end = cut;
+        ranges[i].start = cut;
+    }
+    ranges
+}
+
+/// Pick a language per chunk from its top detection `(code, probability)`.
+/// A chunk detected with less than `min_prob` confidence inherits the
+/// previous chunk's language (or `fallback` for the first chunk), so a
+/// noisy chunk doesn't split a run.
+pub fn assign_languages(
+    detected: &[(&'static str, f32)],
+    min_prob: f32,
+    fallback: &'static str,
+) -> Vec<&'static str> {
+    let mut current = fallback;
+    detected
+        .iter()
+        .map(|&(code, p)| {
+            if p >= min_prob {
+                current = code;
+            }
+            current
+        })
+        .collect()
+}
+
+/// Merge adjacent chunks with the same language into runs, then move each
+/// boundary between runs to the quietest frame within
+/// `BOUNDARY_SEARCH_SECS` of the chunk edge.
+pub fn language_runs(
+    samples: &[f32],
+    ranges: &[Range<usize>],
+    languages: &[&'static str],
+) -> Vec<LanguageRun> {
+    let mut runs: Vec<LanguageRun> = Vec::new();
+    for (range, &language) in ranges.iter().zip(languages) {
+        match runs.last_mut() {
+            Some(last) if last.language == language => last.range.end = range.end,
+            _ => runs.push(LanguageRun {
+                range: range.clone(),
+                language,
+            }),
+        }
+    }
+
+    for i in 1..runs.len() {
+        let cut = boundary_cut(
+            samples,
+            runs[i - 1].range.start,
+            runs[i].range.start,
+            runs[i].range.end,
+        );
+        runs[i - 1].range.end = cut;
+        runs[i].range.start = cut;
+    }
+    runs
+}
+
+/// Where to cut between a range starting at `lo` and one ending at `hi`
+/// that currently meet at `at`: the quietest frame within
+/// `BOUNDARY_SEARCH_SECS`, leaving both ranges non-empty.
+fn boundary_cut(samples: &[f32], lo: usize, at: usize, hi: usize) -> usize {
+    let search = (BOUNDARY_SEARCH_SECS * SAMPLE_RATE as f64) as usize;
+    quietest_point(samples, at, search).clamp(lo + 1, hi - 1)
+}
+
+/// Start of the lowest-energy frame within `search` samples of `at`.
+fn quietest_point(samples: &[f32], at: usize, search: usize) -> usize {
+    let lo = at.saturating_sub(search);
+    let hi = (at + search).min(samples.len());
+    let energy = |start: usize| -> f32 {
+        samples[start..(start + FRAME).min(samples.len())]
+            .iter()
+            .map(|s| s * s)
+            .sum()
+    };
+    (lo..hi)
+        .step_by(FRAME)
+        .min_by(|&a, &b| energy(a).total_cmp(&energy(b)))
+        .unwrap_or(at)
+}
+
+/// Shift segments from a run that starts `offset` samples into the audio
+/// back onto the full timeline and tag them with the run's language.
+pub fn place_segments(segments: &mut [Segment], offset: usize, language: &str) {
+    shift_segments(segments, offset);
+    for seg in segments {
+        seg.language = Some(language.to_string());
+    }
+}
+
+/// Shift segments from audio starting `offset` samples in onto the full
+/// timeline.
+pub fn shift_segments(segments: &mut [Segment], offset: usize) {
+    let offset_secs = offset as f64 / SAMPLE_RATE as f64;
+    for seg in segments {
+        seg.start += offset_secs;
+        seg.end += offset_secs;
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_cover_all_samples() {
+        assert_eq!(chunk_ranges(25, 10), vec![0..10, 10..20, 20..25]);
+        assert_eq!(chunk_ranges(20, 10), vec![0..10, 10..20]);
+        assert!(chunk_ranges(0, 10).is_empty());
+    }
+
+    #[test]
+    fn uncertain_chunks_inherit_previous_language() {
+        let detected = [
+            ("en", 0.3),
+            ("tr", 0.9),
+            ("en", 0.8),
+            ("de", 0.2),
+            ("tr", 0.7),
+        ];
+        assert_eq!(
+            assign_languages(&detected, 0.5, "tr"),
+            vec!["tr", "tr", "en", "en", "tr"]
+        );
+    }
+
+    #[test]
+    fn same_language_chunks_merge_into_runs() {
+        let samples = vec![0.5; 4 * SAMPLE_RATE];
+        let ranges = chunk_ranges(samples.len(), SAMPLE_RATE);
+        let runs = language_runs(&samples, &ranges, &["tr", "tr", "en", "en"]);
+        assert_eq!(runs.len(), 2);
+        assert_eq!((runs[0].language, runs[1].language), ("tr", "en"));
+        assert_eq!(runs[0].range.start, 0);
+        assert_eq!(runs[0].range.end, runs[1].range.start);
+        assert_eq!(runs[1].range.end, samples.len());
+    }
+
+    #[test]
+    fn boundary_moves_to_nearby_silence() {
+        // Loud audio with a short silence 0.5 s before the chunk edge
+        let mut samples = vec![0.5; 4 * SAMPLE_RATE];
+        let silence = 2 * SAMPLE_RATE - SAMPLE_RATE / 2;
+        samples[silence..silence + FRAME].fill(0.0);
+        let ranges = chunk_ranges(samples.len(), 2 * SAMPLE_RATE);
+        let runs = language_runs(&samples, &ranges, &["tr", "en"]);
+        assert_eq!(runs[0].range.end, silence);
+        assert_eq!(runs[1].range.start, silence);
+    }
+
+    #[test]
+    fn quiet_chunks_cut_at_silence() {
+        let mut samples = vec![0.5; 5 * SAMPLE_RATE];
+        let silence = 2 * SAMPLE_RATE + SAMPLE_RATE / 2;
+        samples[silence..silence + FRAME].fill(0.0);
+        let ranges = quiet_chunk_ranges(&samples, 2 * SAMPLE_RATE);
+        assert_eq!(
+            ranges.len(),
+            chunk_ranges(samples.len(), 2 * SAMPLE_RATE).len()
+        );
+        assert_eq!((ranges[0].end, ranges[1].start), (silence, silence));
+        assert_eq!(ranges[2].end, samples.len());
+        assert!(quiet_chunk_ranges(&[], 10).is_empty());
+    }
+
+    #[test]
+    fn segments_shifted_and_tagged() {
+        let mut segments = vec![Segment {
+            start: 1.0,
+            end: 2.5,
+            text: "meeting notes".to_string(),
+            ..Segment::default()
+        }];
+        place_segments(&mut segments, 30 * SAMPLE_RATE, "en");
+        assert_eq!((segments[0].start, segments[0].end), (31.0, 32.5));
+        assert_eq!(segments[0].language.as_deref(), Some("en"));
+    }
+}
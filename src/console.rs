@@ -0,0 +1,73 @@
+//! Progress lines for the user on stderr. Parallel batch workers buffer
+//! their lines and print them as one block per file, so files processed
+//! side by side don't interleave.
+
+use std::cell::RefCell;
+use std::fmt::{self, Write as _};
+
+thread_local! {
+    /// Lines held back by [`buffered`] on this thread, if it is buffering.
+    static BUFFER: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Print a status line, like `eprintln!`, unless this thread is buffering.
+macro_rules! status {
+    ($($arg:tt)*) => {
+        $crate::console::line(format_args!($($arg)*))
+    };
+}
+pub(crate) use status;
+
+/// Print or buffer one line; use [`status!`] instead of calling this.
+pub fn line(args: fmt::Arguments) {
+    BUFFER.with(|buf| match buf.borrow_mut().as_mut() {
+        Some(buf) => {
+            let _ = writeln!(buf, "{args}");
+        }
+        None => eprintln!("{args}"),
+    });
+}
+
+/// Run `work`, collecting its status lines instead of printing them.
+/// Returns its result and the collected text.
+pub fn buffered<R>(work: impl FnOnce() -> R) -> (R, String) {
+    let outer = BUFFER.with(|buf| buf.replace(Some(String::new())));
+    let result = work();
+    let text = BUFFER.with(|buf| buf.replace(outer));
+    (result, text.unwrap_or_default())
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffered_lines_are_returned_not_printed() {
+        let (n, text) = buffered(|| {
+            status!("=== [1/2] a.mp3 ===");
+            status!("       Audio length: {:.1} minutes", 1.5);
+            7
+        });
+        assert_eq!(n, 7);
+        assert_eq!(
+            text,
+            "=== [1/2] a.mp3 ===\n       Audio length: 1.5 minutes\n"
+        );
+    }
+
+    #[test]
+    fn each_thread_has_its_own_buffer() {
+        let (_, text) = buffered(|| {
+            std::thread::scope(|scope| {
+                scope.spawn(|| {
+                    let (_, inner) = buffered(|| status!("b.mp3"));
+                    assert_eq!(inner, "b.mp3\n");
+                });
+            });
+            status!("a.mp3");
+        });
+        assert_eq!(text, "a.mp3\n");
+    }
+}
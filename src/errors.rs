@@ -19,8 +19,11 @@ pub enum AudioError {
     #[error("No audio track found in file")]
     NoTrack,
 
-    #[error("Unsupported audio codec")]
-    UnsupportedCodec,
+    #[error(
+        "Audio codec '{codec}' was recognized, but support for it is not compiled into this build \
+         (convert the file to MP3, WAV or FLAC)"
+    )]
+    CodecNotCompiled { codec: String },
 
     #[error("Audio decode error: {0}")]
     DecodeError(String),
@@ -36,6 +39,18 @@ pub enum AudioError {
 
     #[error("Path is not a file: {path}")]
     NotAFile { path: String },
+
+    #[error("Audio file is empty (0 bytes): {path}")]
+    EmptyFile { path: String },
+
+    #[error("Audio file is locked by another program: {path} — close it and try again")]
+    FileLocked { path: String },
+
+    #[error("Reading audio from stdin requires --input-format (there is no file extension to detect it from)")]
+    StdinFormatRequired,
+
+    #[error("Cannot read audio from stdin: {0}")]
+    StdinRead(#[source] std::io::Error),
 }
 
 // ── Model errors ─────────────────────────────────────────────────────
@@ -54,8 +69,11 @@ pub enum ModelError {
     #[error("HTTP error {status} downloading model from {url}")]
     HttpError { status: u16, url: String },
 
-    #[error("Download timed out after {seconds}s")]
-    Timeout { seconds: u64 },
+    #[error("{stage} timed out{}", .seconds.map(|s| format!(" after {s}s")).unwrap_or_default())]
+    Timeout {
+        stage: &'static str,
+        seconds: Option<u64>,
+    },
 
     #[error("Model file too small ({size} bytes) — expected at least {expected} bytes for {model} model")]
     FileTooSmall {
@@ -79,6 +97,16 @@ pub enum ModelError {
 
     #[error("Cannot rename temp file to final path: {0}")]
     RenameFailed(String),
+
+    #[error("Cannot read cache directory: {path}")]
+    CacheRead {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("{failed} of {total} cached models failed verification")]
+    VerifyFailed { failed: usize, total: usize },
 }
 
 // ── Transcription errors ─────────────────────────────────────────────
@@ -115,6 +143,45 @@ pub enum OutputError {
 
     #[error("Failed to write output: {0}")]
     WriteFailed(String),
+
+    #[error("Output file already exists: {path} (use --overwrite or --append)")]
+    AlreadyExists { path: String },
+
+    #[error("Cannot write checkpoint: {path}")]
+    CheckpointWrite {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Output directory is not writable: {path}")]
+    DirNotWritable {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+// ── Config errors ────────────────────────────────────────────────────
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Cannot read config file: {path}")]
+    ReadFailed {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid rule at {path}:{line}: {reason}")]
+    InvalidRule {
+        path: String,
+        line: usize,
+        reason: String,
+    },
+
+    #[error("Invalid --header-template: {reason}")]
+    InvalidHeaderTemplate { reason: String },
 }
 
 // ── Exit codes ───────────────────────────────────────────────────────
@@ -141,9 +208,36 @@ impl ExitCode {
     // Output errors (40)
     pub const OUTPUT_WRITE: i32 = 40;
 
+    // Config errors (50)
+    pub const CONFIG: i32 = 50;
+
     // Unknown (99)
     pub const UNKNOWN: i32 = 99;
 
+    /// Every exit code with its constant name and a one-line description.
+    pub const TABLE: &'static [(i32, &'static str, &'static str)] = &[
+        (Self::SUCCESS, "SUCCESS", "Success"),
+        (Self::AUDIO_INPUT, "AUDIO_INPUT", "Audio input error (file not found, empty, locked, unsupported format)"),
+        (Self::AUDIO_DECODE, "AUDIO_DECODE", "Audio decode error (bad codec, corrupt file)"),
+        (Self::AUDIO_VALIDATION, "AUDIO_VALIDATION", "Audio validation error (empty, too short, too long)"),
+        (Self::MODEL_NOT_FOUND, "MODEL_NOT_FOUND", "Model not found (no cache directory)"),
+        (Self::MODEL_DOWNLOAD, "MODEL_DOWNLOAD", "Model download failed (network, timeout)"),
+        (Self::MODEL_INTEGRITY, "MODEL_INTEGRITY", "Model integrity error (corrupt download, failed `verify`)"),
+        (Self::MODEL_LOAD, "MODEL_LOAD", "Model load error"),
+        (Self::TRANSCRIPTION, "TRANSCRIPTION", "Transcription error"),
+        (Self::OUTPUT_WRITE, "OUTPUT_WRITE", "Output write error"),
+        (Self::CONFIG, "CONFIG", "Config error (unreadable word list or rules file, invalid template)"),
+        (Self::UNKNOWN, "UNKNOWN", "Unknown error"),
+    ];
+
+    /// `TABLE` as tab-separated lines (`code`, `name`, `description`).
+    pub fn list() -> String {
+        Self::TABLE
+            .iter()
+            .map(|(code, name, description)| format!("{code}\t{name}\t{description}\n"))
+            .collect()
+    }
+
     /// Walk the anyhow error chain and return the appropriate exit code.
     pub fn from_error(err: &anyhow::Error) -> i32 {
         for cause in err.chain() {
@@ -151,9 +245,13 @@ impl ExitCode {
                 return match e {
                     AudioError::FileOpen { .. }
                     | AudioError::UnsupportedFormat
-                    | AudioError::NotAFile { .. } => Self::AUDIO_INPUT,
+                    | AudioError::NotAFile { .. }
+                    | AudioError::EmptyFile { .. }
+                    | AudioError::FileLocked { .. }
+                    | AudioError::StdinFormatRequired
+                    | AudioError::StdinRead(_) => Self::AUDIO_INPUT,
                     AudioError::NoTrack
-                    | AudioError::UnsupportedCodec
+                    | AudioError::CodecNotCompiled { .. }
                     | AudioError::DecodeError(_) => Self::AUDIO_DECODE,
                     AudioError::EmptyAudio | AudioError::TooShort { .. } | AudioError::TooLong { .. } => {
                         Self::AUDIO_VALIDATION
@@ -162,14 +260,16 @@ impl ExitCode {
             }
             if let Some(e) = cause.downcast_ref::<ModelError>() {
                 return match e {
-                    ModelError::NoCacheDir | ModelError::CacheDirCreation { .. } => {
-                        Self::MODEL_NOT_FOUND
-                    }
+                    ModelError::NoCacheDir
+                    | ModelError::CacheDirCreation { .. }
+                    | ModelError::CacheRead { .. } => Self::MODEL_NOT_FOUND,
                     ModelError::InsufficientDiskSpace
                     | ModelError::DownloadFailed { .. }
                     | ModelError::HttpError { .. }
                     | ModelError::Timeout { .. } => Self::MODEL_DOWNLOAD,
-                    ModelError::FileTooSmall { .. } => Self::MODEL_INTEGRITY,
+                    ModelError::FileTooSmall { .. } | ModelError::VerifyFailed { .. } => {
+                        Self::MODEL_INTEGRITY
+                    }
                     ModelError::LoadFailed(_)
                     | ModelError::InvalidPath(_)
                     | ModelError::RenameFailed(_) => Self::MODEL_LOAD,
@@ -181,7 +281,31 @@ impl ExitCode {
             if cause.downcast_ref::<OutputError>().is_some() {
                 return Self::OUTPUT_WRITE;
             }
+            if cause.downcast_ref::<ConfigError>().is_some() {
+                return Self::CONFIG;
+            }
         }
         Self::UNKNOWN
     }
 }
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_code_list_covers_every_code() {
+        let list = ExitCode::list();
+        let codes: Vec<i32> = list
+            .lines()
+            .map(|line| line.split('\t').next().unwrap().parse().unwrap())
+            .collect();
+        for code in [0, 10, 11, 12, 20, 21, 22, 23, 30, 40, 50, 99] {
+            assert!(codes.contains(&code), "exit code {code} missing");
+        }
+        assert!(list.contains("40\tOUTPUT_WRITE\tOutput write error\n"));
+        assert!(list.lines().all(|line| line.split('\t').count() == 3));
+    }
+}
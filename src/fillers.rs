@@ -0,0 +1,162 @@
+//! Removal of filler sounds (`ıı`, `ee`, `hmm`) from segment text.
+//!
+//! Only whole words are removed. `şey` is also a real word ("thing"), so
+//! it is only removed when punctuation isolates it as a hesitation
+//! (`Bu, şey, güzel` or a leading `Şey...`), never inside a phrase like
+//! `bir şey söyle`.
+
+use std::path::Path;
+
+use crate::errors::ConfigError;
+use crate::numbers::split_punctuation;
+use crate::postprocess::{load_word_list, turkish_lowercase};
+
+/// Fillers removed wherever they appear as a whole word.
+static DEFAULT_FILLERS: &[&str] = &[
+    "ıı", "ııı", "ıııı", "ee", "eee", "eeee", "ım", "ımm", "hı", "hım", "hımm", "hmm", "mm", "mmm",
+];
+
+/// Fillers that are also real words; removed only when isolated.
+static GUARDED_FILLERS: &[&str] = &["şey"];
+
+/// Punctuation that marks a pause around a guarded filler.
+const PAUSE_PUNCTUATION: &[char] = &[',', '.', '…', ';', '-', '—'];
+
+/// Filler word lists for `--remove-fillers`, lowercased.
+#[derive(Clone, Debug)]
+pub struct Fillers {
+    words: Vec<String>,
+    guarded: Vec<String>,
+}
+
+impl Default for Fillers {
+    fn default() -> Self {
+        Self {
+            words: DEFAULT_FILLERS.iter().map(|w| w.to_string()).collect(),
+            guarded: GUARDED_FILLERS.iter().map(|w| w.to_string()).collect(),
+        }
+    }
+}
+
+impl Fillers {
+    /// Default list extended with the words in `path` (one per line).
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let mut fillers = Self::default();
+        fillers.words.extend(load_word_list(path)?);
+        Ok(fillers)
+    }
+
+    /// Remove filler words from `text`, keeping sentence punctuation that
+    /// was attached to them and re-capitalizing a sentence whose first
+    /// word was removed. May return an empty string.
+    pub fn remove(&self, text: &str) -> String {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let mut out: Vec<String> = Vec::with_capacity(tokens.len());
+        let mut capitalize_next = false;
+
+        for (i, token) in tokens.iter().enumerate() {
+            let (_, core, suffix) = split_punctuation(token);
+            let word = turkish_lowercase(core);
+            let remove = if self.words.contains(&word) {
+                true
+            } else if self.guarded.contains(&word) {
+                let pause_before = match out.last() {
+                    None => true,
+                    Some(prev) => prev.ends_with(PAUSE_PUNCTUATION),
+                };
+                let pause_after = i + 1 == tokens.len() || suffix.contains(PAUSE_PUNCTUATION);
+                pause_before && pause_after
+            } else {
+                false
+            };
+
+            if !remove {
+                let mut token = token.to_string();
+                if capitalize_next {
+                    token = capitalize(&token);
+                    capitalize_next = false;
+                }
+                out.push(token);
+                continue;
+            }
+
+            // Keep sentence-ending punctuation on the previous word
+            let ending: String = suffix.chars().filter(|c| ".?!…".contains(*c)).collect();
+            if let Some(prev) = out.last_mut() {
+                if !ending.is_empty() && !prev.ends_with(['.', '?', '!', '…']) {
+                    *prev = format!("{}{ending}", prev.trim_end_matches([',', ';']));
+                }
+            } else if core.chars().next().is_some_and(char::is_uppercase) {
+                capitalize_next = true;
+            }
+        }
+
+        out.join(" ")
+    }
+}
+
+/// Uppercase the first letter with Turkish rules (`i` → `İ`).
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some('i') => format!("İ{}", chars.as_str()),
+        Some(c) => format!("{}{}", c.to_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remove(text: &str) -> String {
+        Fillers::default().remove(text)
+    }
+
+    #[test]
+    fn fillers_removed_as_whole_words() {
+        assert_eq!(
+            remove("Bugün ıı toplantı ee yapacağız."),
+            "Bugün toplantı yapacağız."
+        );
+        assert_eq!(remove("Iı, bugün geldik."), "Bugün geldik.");
+        assert_eq!(remove("Iı, işte geldik."), "İşte geldik.");
+        // "eee" inside a word is untouched
+        assert_eq!(remove("Beeen dedi."), "Beeen dedi.");
+    }
+
+    #[test]
+    fn double_spaces_collapsed() {
+        assert_eq!(remove("bir  ıı   iki"), "bir iki");
+        assert_eq!(remove("geldim ıı."), "geldim.");
+        assert_eq!(remove("geldim, hmm."), "geldim.");
+    }
+
+    #[test]
+    fn segment_of_only_fillers_becomes_empty() {
+        assert_eq!(remove("Iı... ee, hmm."), "");
+    }
+
+    #[test]
+    fn sey_as_real_word_kept() {
+        assert_eq!(remove("Bir şey söyleyeceğim."), "Bir şey söyleyeceğim.");
+        assert_eq!(remove("Her şey yolunda."), "Her şey yolunda.");
+        assert_eq!(remove("O şey, çok pahalı."), "O şey, çok pahalı.");
+    }
+
+    #[test]
+    fn isolated_sey_removed() {
+        assert_eq!(remove("Bu, şey, çok güzel."), "Bu, çok güzel.");
+        assert_eq!(remove("Şey... yarın gelirim."), "Yarın gelirim.");
+        assert_eq!(remove("Şey."), "");
+    }
+
+    #[test]
+    fn extra_fillers_extend_defaults() {
+        let mut fillers = Fillers::default();
+        fillers.words.push("yani".to_string());
+        assert_eq!(fillers.remove("Yani ıı geldik."), "Geldik.");
+    }
+}
@@ -0,0 +1,208 @@
+//! Custom header block for text transcripts (`--header-template`).
+//!
+//! A template is free text with `{source}`, `{model}`, `{duration}`,
+//! `{date}` and `{language}` placeholders. `{{` and `}}` write literal
+//! braces and `\n` starts a new line, so a multi-line header can be given
+//! as a single shell argument.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::errors::ConfigError;
+
+/// Placeholder names accepted in a template.
+const FIELDS: &[&str] = &["source", "model", "duration", "date", "language"];
+
+/// Values substituted into a header template.
+pub struct HeaderFields<'a> {
+    /// Audio file name
+    pub source: &'a str,
+    /// Model size, e.g. `medium`
+    pub model: &'a str,
+    /// Inference time in seconds
+    pub duration: f64,
+    /// Date the transcript was written (`YYYY-MM-DD`, UTC)
+    pub date: &'a str,
+    /// Language code(s) of the transcript, comma-separated
+    pub language: &'a str,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Piece {
+    Text(String),
+    Field(&'static str),
+}
+
+/// A parsed `--header-template`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeaderTemplate {
+    pieces: Vec<Piece>,
+}
+
+impl HeaderTemplate {
+    /// Parse `template`, rejecting unknown placeholders and unmatched
+    /// braces.
+    pub fn parse(template: &str) -> Result<Self, ConfigError> {
+        let invalid = |reason: String| ConfigError::InvalidHeaderTemplate { reason };
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '\\' if chars.peek() == Some(&'n') => {
+                    chars.next();
+                    text.push('\n');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(c);
+                    }
+                    if !closed {
+                        return Err(invalid(format!("unclosed placeholder `{{{name}`")));
+                    }
+                    let Some(field) = FIELDS.iter().find(|&&f| f == name) else {
+                        return Err(invalid(format!(
+                            "unknown placeholder `{{{name}}}` (expected one of {})",
+                            FIELDS
+                                .iter()
+                                .map(|f| format!("{{{f}}}"))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )));
+                    };
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(Piece::Field(field));
+                }
+                '}' => {
+                    return Err(invalid(
+                        "unmatched `}` (write `}}` for a literal brace)".into(),
+                    ))
+                }
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        Ok(Self { pieces })
+    }
+
+    /// Whether the template renders to nothing (suppresses the header).
+    pub fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+
+    /// Substitute `fields` into the template.
+    pub fn render(&self, fields: &HeaderFields) -> String {
+        self.pieces
+            .iter()
+            .map(|piece| match piece {
+                Piece::Text(text) => text.clone(),
+                Piece::Field("source") => fields.source.to_string(),
+                Piece::Field("model") => format!("whisper-{}", fields.model),
+                Piece::Field("duration") => format!("{:.1}s", fields.duration),
+                Piece::Field("date") => fields.date.to_string(),
+                Piece::Field(_) => fields.language.to_string(),
+            })
+            .collect()
+    }
+}
+
+/// Today's date in UTC as `YYYY-MM-DD`.
+pub fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    civil_date(secs / 86_400)
+}
+
+/// Convert days since 1970-01-01 to `YYYY-MM-DD` (proleptic Gregorian).
+fn civil_date(days: u64) -> String {
+    // Howard Hinnant's days-to-civil algorithm, shifted to a March-based year
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> HeaderFields<'static> {
+        HeaderFields {
+            source: "durusma.mp3",
+            model: "medium",
+            duration: 12.34,
+            date: "2024-03-01",
+            language: "tr",
+        }
+    }
+
+    #[test]
+    fn placeholders_substituted() {
+        let template = HeaderTemplate::parse(
+            "Dosya 2024/117\\nKaynak: {source} ({model}, {duration})\\n{date} [{language}]",
+        )
+        .unwrap();
+        assert_eq!(
+            template.render(&fields()),
+            "Dosya 2024/117\nKaynak: durusma.mp3 (whisper-medium, 12.3s)\n2024-03-01 [tr]"
+        );
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        let template = HeaderTemplate::parse("{{source}} = {source}").unwrap();
+        assert_eq!(template.render(&fields()), "{source} = durusma.mp3");
+    }
+
+    #[test]
+    fn unknown_placeholder_rejected() {
+        let err = HeaderTemplate::parse("Case {case_no}: {source}").unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("unknown placeholder `{case_no}`"), "{msg}");
+        assert!(msg.contains("{source}, {model}"), "{msg}");
+
+        assert!(HeaderTemplate::parse("{source").is_err());
+        assert!(HeaderTemplate::parse("source}").is_err());
+    }
+
+    #[test]
+    fn empty_template_is_empty() {
+        assert!(HeaderTemplate::parse("").unwrap().is_empty());
+        assert!(!HeaderTemplate::parse("x").unwrap().is_empty());
+    }
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_date(0), "1970-01-01");
+        assert_eq!(civil_date(11_016), "2000-02-29");
+        assert_eq!(civil_date(19_783), "2024-03-01");
+    }
+}
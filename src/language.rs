@@ -0,0 +1,59 @@
+//! Helpers for Whisper's spoken-language detection results.
+
+/// A language code paired with its detection probability.
+pub type Candidate<'a> = (&'a str, f32);
+
+/// Return the `n` most probable languages, highest probability first.
+pub fn top_candidates<'a>(probs: &[Candidate<'a>], n: usize) -> Vec<Candidate<'a>> {
+    let mut sorted = probs.to_vec();
+    sorted.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    sorted.truncate(n);
+    sorted
+}
+
+/// Format candidates as aligned `code  probability%` lines.
+pub fn format_candidates(candidates: &[Candidate<'_>]) -> String {
+    candidates
+        .iter()
+        .enumerate()
+        .map(|(i, (code, p))| format!("{}. {code:<5} {:>5.1}%\n", i + 1, p * 100.0))
+        .collect()
+}
+
+/// `--language-hint`: `detected` if it is one of `hints`, otherwise the
+/// first hint. An empty hint list keeps the detection.
+pub fn constrain<'a>(detected: &'a str, hints: &[&'a str]) -> &'a str {
+    match hints.first() {
+        Some(&first) if !hints.contains(&detected) => first,
+        _ => detected,
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn top_candidates_sorted_by_probability() {
+        let probs = [("en", 0.20), ("tr", 0.70), ("de", 0.02), ("az", 0.08)];
+        let top = top_candidates(&probs, 3);
+        assert_eq!(top, vec![("tr", 0.70), ("en", 0.20), ("az", 0.08)]);
+    }
+
+    #[test]
+    fn detection_constrained_to_hints() {
+        let hints = ["tr", "en"];
+        assert_eq!(constrain("en", &hints), "en", "hinted language kept");
+        assert_eq!(constrain("tr", &hints), "tr");
+        assert_eq!(constrain("az", &hints), "tr", "falls back to first hint");
+        assert_eq!(constrain("az", &[]), "az", "no hints: detection kept");
+    }
+
+    #[test]
+    fn candidates_formatted_as_percentages() {
+        let out = format_candidates(&[("tr", 0.925), ("en", 0.05)]);
+        assert_eq!(out, "1. tr     92.5%\n2. en      5.0%\n");
+    }
+}
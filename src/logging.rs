@@ -1,6 +1,7 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_subscriber::fmt::time::uptime;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
@@ -16,29 +17,56 @@ pub enum Verbosity {
     Verbose,
 }
 
+/// How often the log file is rolled over (`--log-rotation`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogRotation {
+    Daily,
+    Hourly,
+    /// A single, never-rolled log file
+    Never,
+}
+
+impl LogRotation {
+    fn rotation(self) -> Rotation {
+        match self {
+            LogRotation::Daily => Rotation::DAILY,
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Never => Rotation::NEVER,
+        }
+    }
+}
+
+/// Log file settings from the CLI.
+pub struct LogFileOptions<'a> {
+    /// Custom log file path (`--log-file`)
+    pub path: Option<&'a PathBuf>,
+    pub rotation: LogRotation,
+    /// Keep at most this many rolled files (`--log-max-files`)
+    pub max_files: Option<usize>,
+}
+
 /// Default log directory: `~/.cache/whisper-models/logs/`
 fn default_log_dir() -> Option<PathBuf> {
     dirs::home_dir().map(|h| h.join(".cache").join("whisper-models").join("logs"))
 }
 
-/// Initialize dual-layer tracing: colored console + daily-rolling file.
+/// Initialize dual-layer tracing: colored console + rolling file.
 ///
 /// Returns a `WorkerGuard` that **must** be kept alive for the program's
 /// lifetime — dropping it flushes the file writer.
-pub fn init(verbosity: Verbosity, log_file_override: Option<&PathBuf>) -> Option<WorkerGuard> {
-    let console_filter = match verbosity {
-        Verbosity::Quiet => EnvFilter::new("error"),
-        Verbosity::Normal => EnvFilter::new("info"),
-        Verbosity::Verbose => EnvFilter::new("debug"),
-    };
+pub fn init(verbosity: Verbosity, log_file: &LogFileOptions) -> Option<WorkerGuard> {
+    let rust_log = std::env::var(EnvFilter::DEFAULT_ENV).ok();
+    let console_filter = console_filter(&verbosity, rust_log.as_deref());
 
+    // stderr keeps stdout clean for transcripts written with `--output -`
     let console_layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
         .compact()
         .with_target(false)
         .with_filter(console_filter);
 
     // Try to set up a file layer; if it fails, run console-only.
-    match build_file_writer(log_file_override) {
+    match build_file_writer(log_file) {
         Some((non_blocking, guard)) => {
             let file_layer = tracing_subscriber::fmt::layer()
                 .with_writer(non_blocking)
@@ -65,11 +93,29 @@ pub fn init(verbosity: Verbosity, log_file_override: Option<&PathBuf>) -> Option
     }
 }
 
+/// Console filter: `RUST_LOG` when set to a valid directive list, otherwise
+/// the level chosen by `--quiet`/`--verbose`.
+fn console_filter(verbosity: &Verbosity, rust_log: Option<&str>) -> EnvFilter {
+    if let Some(directives) = rust_log.filter(|d| !d.trim().is_empty()) {
+        match EnvFilter::try_new(directives) {
+            Ok(filter) => return filter,
+            Err(e) => eprintln!("Warning: ignoring invalid RUST_LOG ({e})"),
+        }
+    }
+
+    match verbosity {
+        Verbosity::Quiet => EnvFilter::new("error"),
+        Verbosity::Normal => EnvFilter::new("info"),
+        Verbosity::Verbose => EnvFilter::new("debug"),
+    }
+}
+
 /// Create the non-blocking file writer. Returns `None` if the log directory
 /// cannot be created (e.g. read-only filesystem).
 fn build_file_writer(
-    override_path: Option<&PathBuf>,
+    opts: &LogFileOptions,
 ) -> Option<(tracing_appender::non_blocking::NonBlocking, WorkerGuard)> {
+    let override_path = opts.path;
     let log_dir = if let Some(p) = override_path {
         p.parent()
             .map(|d| d.to_path_buf())
@@ -88,8 +134,97 @@ fn build_file_writer(
         "transcriber.log".to_string()
     };
 
-    let file_appender = tracing_appender::rolling::daily(&log_dir, &file_name);
+    if let Some(max_files) = opts.max_files {
+        prune_old_logs(&log_dir, &file_name, max_files);
+    }
+
+    let file_appender = RollingFileAppender::new(opts.rotation.rotation(), &log_dir, &file_name);
     let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
 
     Some((non_blocking, guard))
 }
+
+/// Delete the oldest rolled `<file_name>.*` files in `dir` so that at
+/// most `max_files` remain. Rolled names end in a date (`.2024-05-01` or
+/// `.2024-05-01-13`), so name order is age order. Failures are ignored.
+fn prune_old_logs(dir: &Path, file_name: &str, max_files: usize) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let prefix = format!("{file_name}.");
+    let mut rolled: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.is_file()
+                && p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with(&prefix))
+        })
+        .collect();
+    if rolled.len() <= max_files {
+        return;
+    }
+    rolled.sort();
+    let excess = rolled.len() - max_files;
+    for path in &rolled[..excess] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::ScratchDir;
+
+    #[test]
+    fn rotation_selects_appender_policy() {
+        assert_eq!(LogRotation::Daily.rotation(), Rotation::DAILY);
+        assert_eq!(LogRotation::Hourly.rotation(), Rotation::HOURLY);
+        assert_eq!(LogRotation::Never.rotation(), Rotation::NEVER);
+    }
+
+    #[test]
+    fn prune_keeps_newest_rolled_files() {
+        let dir = ScratchDir::new("logs");
+        for name in [
+            "transcriber.log.2024-05-03",
+            "transcriber.log.2024-05-01",
+            "transcriber.log.2024-05-04",
+            "transcriber.log.2024-05-02",
+            "other.log.2024-05-01",
+        ] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        prune_old_logs(&dir, "transcriber.log", 2);
+
+        let mut left: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            vec![
+                "other.log.2024-05-01",
+                "transcriber.log.2024-05-03",
+                "transcriber.log.2024-05-04",
+            ]
+        );
+    }
+
+    #[test]
+    fn rust_log_overrides_verbosity() {
+        let filter = console_filter(&Verbosity::Quiet, Some("turkish_transcriber::model=debug"));
+        assert_eq!(filter.to_string(), "turkish_transcriber::model=debug");
+    }
+
+    #[test]
+    fn unset_rust_log_falls_back_to_flags() {
+        assert_eq!(console_filter(&Verbosity::Quiet, None).to_string(), "error");
+        assert_eq!(console_filter(&Verbosity::Normal, Some("")).to_string(), "info");
+        assert_eq!(console_filter(&Verbosity::Verbose, None).to_string(), "debug");
+    }
+}
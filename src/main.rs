@@ -1,39 +1,327 @@
 mod audio;
+mod batch;
+mod checkpoint;
+mod chunking;
+mod console;
 mod errors;
+mod fillers;
+mod header;
+mod language;
 mod logging;
 mod model;
+mod numbers;
+mod output;
 mod postprocess;
+mod profile;
+mod review;
+mod segments;
+mod selftest;
+mod sentences;
+mod subtitle;
+#[cfg(test)]
+mod testutil;
 mod transcribe;
+mod vad;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
-use clap::Parser;
-use tracing::{debug, error, info};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use tracing::{debug, error, info, warn};
 
-use errors::{AudioError, ExitCode};
-use logging::Verbosity;
+use console::status;
+use errors::{AudioError, ExitCode, OutputError};
+use logging::{LogRotation, Verbosity};
+
+/// Combined Whisper context memory above which `--jobs` prints a warning.
+const PARALLEL_MEMORY_WARN_MB: u64 = 8_000;
 
 /// Transcribe Turkish audio to text using Whisper.
 #[derive(Parser)]
 #[command(name = "transcriber", version, about)]
 struct Cli {
-    /// Path to audio file (opens file picker if omitted)
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Path to audio file, a directory of audio files, or `-` for stdin
+    /// (opens file picker if omitted)
     file: Option<PathBuf>,
 
+    /// Audio format to assume instead of detecting it from the file extension
+    #[arg(long, value_parser = ["mp3", "wav", "flac", "ogg", "m4a", "mp4", "aac"])]
+    input_format: Option<String>,
+
     /// Whisper model size
     #[arg(
         short,
         long,
         default_value = "medium",
-        value_parser = ["tiny", "base", "small", "medium", "large-v3"]
+        value_parser = ["tiny", "base", "small", "medium", "large-v3"],
+        global = true
     )]
     model: String,
 
-    /// Output text file path
+    /// Whether `--model` was given rather than defaulted
+    #[arg(skip)]
+    model_given: bool,
+
+    /// Download and use a quantized variant of the model (smaller and
+    /// faster, slightly less accurate)
+    #[arg(long, default_value = "none", value_parser = ["none", "q5_0", "q5_1", "q8_0"])]
+    quantization: String,
+
+    /// Seconds to wait for the model server to accept a connection
+    /// (0 = no limit)
+    #[arg(long, value_name = "SECS", default_value_t = model::CONNECT_TIMEOUT.as_secs())]
+    connect_timeout: u64,
+
+    /// Seconds allowed for the whole model download (0 = no limit)
+    #[arg(long, value_name = "SECS", default_value_t = model::DOWNLOAD_TIMEOUT.as_secs())]
+    download_timeout: u64,
+
+    /// Keep the partial file of a failed model download (renamed to
+    /// `.part.<attempt>`) instead of deleting it
+    #[arg(long)]
+    keep_partial_on_failure: bool,
+
+    /// Base URL to download `ggml-<model>.bin` from instead of HuggingFace,
+    /// e.g. a team mirror
+    #[arg(long, value_name = "URL", env = "WHISPER_MODEL_URL", value_parser = http_url)]
+    model_url: Option<reqwest::Url>,
+
+    /// Header sent with model downloads from --model-url, e.g.
+    /// `Authorization: Bearer <token>`; its value is never logged
+    #[arg(long, value_name = "HEADER", env = "WHISPER_MODEL_AUTH", hide_env_values = true)]
+    auth_header: Option<model::AuthHeader>,
+
+    /// Only detect the spoken language and print the top 3 candidates
+    #[arg(long)]
+    detect_language: bool,
+
+    /// Check that the audio decodes and the model is available, then exit
+    /// without transcribing
+    #[arg(long, conflicts_with = "detect_language")]
+    dry_run: bool,
+
+    /// Decode the audio and print its sample rate, channels, duration and
+    /// levels, then exit without loading the model
+    #[arg(long, conflicts_with_all = ["detect_language", "dry_run"])]
+    stats_only: bool,
+
+    /// Output file path (output directory in batch mode, `-` for stdout).
+    /// With several formats, each format replaces the extension
     #[arg(short, long)]
     output: Option<PathBuf>,
 
+    /// Write a batch summary index (JSON, or CSV if the path ends in .csv)
+    #[arg(long, value_name = "PATH")]
+    index: Option<PathBuf>,
+
+    /// Number of files to transcribe at once in batch mode. Each job loads
+    /// its own copy of the model
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    jobs: u64,
+
+    /// Skip inputs whose transcript is newer than the audio file
+    #[arg(long)]
+    skip_existing: bool,
+
+    /// Replace the output file if it already exists
+    #[arg(long)]
+    overwrite: bool,
+
+    /// Append to the output file if it already exists
+    #[arg(long, conflicts_with = "overwrite")]
+    append: bool,
+
+    /// Output formats, comma-separated (`plain` is the bare text with no
+    /// header or timestamps, `lines` is one sentence per line, `review`
+    /// marks low-confidence words for editing)
+    #[arg(
+        long,
+        default_value = "txt",
+        value_delimiter = ',',
+        value_parser = ["txt", "plain", "lines", "srt", "vtt", "json", "review"]
+    )]
+    format: Vec<String>,
+
+    /// Byte encoding of output files (`utf8-bom` adds a byte-order mark so
+    /// Notepad and Excel detect UTF-8)
+    #[arg(long, default_value = "utf8", value_parser = ["utf8", "utf8-bom"])]
+    output_encoding: String,
+
+    /// Write a `<output>.sha256` checksum file next to each output
+    #[arg(long)]
+    write_checksum: bool,
+
+    /// Omit the header block from text output
+    #[arg(long)]
+    no_header: bool,
+
+    /// Custom header for text output, with {source}, {model}, {duration},
+    /// {date} and {language} placeholders and `\n` for line breaks; an
+    /// empty template omits the header
+    #[arg(long, value_name = "TEMPLATE", conflicts_with = "no_header")]
+    header_template: Option<String>,
+
+    /// How the source path appears in the header and JSON: file `name`,
+    /// path `relative` to the input directory (or the working directory),
+    /// or the full `absolute` path
+    #[arg(long, default_value = "name", value_parser = ["name", "relative", "absolute"])]
+    path_style: String,
+
+    /// Omit the timestamped section from text output
+    #[arg(long)]
+    no_timestamps: bool,
+
+    /// Mark silences longer than this many seconds with a `[pause: Ns]` line
+    #[arg(long, value_name = "SECONDS")]
+    mark_pauses: Option<f64>,
+
+    /// Mark likely speaker turns at long silences (heuristic, not diarization):
+    /// `change` inserts a marker line, `--mark-speakers=labels` alternates
+    /// Speaker 1/2
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "change",
+        value_parser = ["change", "labels"]
+    )]
+    mark_speakers: Option<String>,
+
+    /// Silence (seconds) treated as a speaker turn with --mark-speakers
+    #[arg(long, value_name = "SECONDS", default_value_t = 2.0, requires = "mark_speakers")]
+    speaker_gap: f64,
+
+    /// Have Whisper itself end segments at about N characters (enables
+    /// token-level timestamps, which makes inference somewhat slower)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    segment_max_chars: Option<u32>,
+
+    /// Maximum characters per subtitle line (SRT/VTT)
+    #[arg(long, default_value_t = subtitle::DEFAULT_MAX_LINE_LENGTH)]
+    max_line_length: usize,
+
+    /// Replacement rules file (`from => to` per line, `regex:` prefix for patterns)
+    #[arg(long, value_name = "PATH")]
+    replacements: Option<PathBuf>,
+
+    /// Normalize numbers to `digits` (yirmi dört → 24) or `words` (24 → yirmi dört)
+    #[arg(long, value_parser = ["digits", "words"])]
+    numbers: Option<String>,
+
+    /// Strip filler sounds (ıı, ee, hmm; isolated "şey") from the transcript
+    #[arg(long)]
+    remove_fillers: bool,
+
+    /// Extra filler words for --remove-fillers (one word per line)
+    #[arg(long, value_name = "PATH", requires = "remove_fillers")]
+    filler_words: Option<PathBuf>,
+
+    /// Word list to mask in the transcript (one word per line)
+    #[arg(long, value_name = "PATH")]
+    mask_words: Option<PathBuf>,
+
+    /// Replacement for masked words (default: asterisks of equal length)
+    #[arg(long, requires = "mask_words")]
+    mask_token: Option<String>,
+
+    /// Remove segments whose average token probability is below this
+    #[arg(long, value_name = "PROB", value_parser = probability)]
+    drop_below: Option<f32>,
+
+    /// Append `[low confidence]` to segments whose average token
+    /// probability is below this
+    #[arg(long, value_name = "PROB", value_parser = probability)]
+    flag_below: Option<f32>,
+
+    /// Drop segments whose text compresses better than this ratio, which
+    /// catches looped hallucinations (Whisper uses 2.4)
+    #[arg(long, value_name = "RATIO", require_equals = true, value_parser = positive_ratio)]
+    max_compression_ratio: Option<Option<f32>>,
+
+    /// Languages the audio may be in, comma-separated, most likely first:
+    /// the detected language is used if listed, otherwise the first one
+    #[arg(long, value_name = "CODES", value_delimiter = ',', value_parser = language_code)]
+    language_hint: Vec<&'static str>,
+
+    /// Clamp each segment to its first and last word using token
+    /// timestamps, so subtitles don't linger into the following silence
+    #[arg(long)]
+    tighten_timestamps: bool,
+
+    /// Save progress to a `.partial` file next to the output after every
+    /// 30 s chunk, and resume from it if the run is restarted
+    #[arg(long, conflicts_with = "multilingual")]
+    checkpoint: bool,
+
+    /// Save the audio exactly as Whisper receives it (16 kHz mono, after
+    /// --vad) to a WAV file, for diagnosing preprocessing problems
+    #[arg(long, value_name = "PATH")]
+    dump_audio: Option<PathBuf>,
+
+    /// Experimental: detect the language of each 30 s chunk and transcribe
+    /// it in that language (slower; tags segments in JSON output)
+    #[arg(long)]
+    multilingual: bool,
+
+    /// Skip non-speech regions (music, long pauses) using an energy-based
+    /// voice activity detector, and transcribe only the speech
+    #[arg(long)]
+    vad: bool,
+
+    /// Frame level in dBFS at or above which --vad counts audio as speech
+    #[arg(
+        long,
+        value_name = "DB",
+        default_value_t = vad::DEFAULT_THRESHOLD_DB,
+        allow_negative_numbers = true,
+        requires = "vad"
+    )]
+    vad_threshold: f32,
+
+    /// Shortest sound (ms) --vad treats as speech rather than noise
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = vad::DEFAULT_MIN_SPEECH_MS,
+        requires = "vad"
+    )]
+    vad_min_speech: u32,
+
+    /// Beam width for decoding (default: from the model's profile)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(i32).range(1..=16))]
+    beam_size: Option<i32>,
+
+    /// Temperature step when Whisper re-decodes a failed window
+    /// (default: from the model's profile)
+    #[arg(long, value_name = "STEP", value_parser = probability)]
+    temperature_inc: Option<f32>,
+
+    /// No-speech probability above which a window counts as silence
+    /// (default: from the model's profile)
+    #[arg(long, value_name = "P", value_parser = probability)]
+    no_speech_threshold: Option<f32>,
+
+    /// Suppress blank output at the start of each window (Whisper default)
+    #[arg(long, value_name = "BOOL", default_value_t = true, action = clap::ArgAction::Set)]
+    suppress_blank: bool,
+
+    /// Suppress non-speech tokens such as `[music]` or `[applause]`
+    #[arg(long, value_name = "BOOL", default_value_t = false, action = clap::ArgAction::Set)]
+    suppress_nst: bool,
+
+    /// Don't retry with relaxed settings when no speech is found
+    #[arg(long)]
+    no_retry: bool,
+
     /// Enable verbose (debug) console output
     #[arg(long)]
     verbose: bool,
@@ -45,10 +333,57 @@ struct Cli {
     /// Custom log file path (default: ~/.cache/whisper-models/logs/transcriber.log)
     #[arg(long)]
     log_file: Option<PathBuf>,
+
+    /// How often the log file is rolled over
+    #[arg(long, default_value = "daily", value_parser = ["daily", "hourly", "never"])]
+    log_rotation: String,
+
+    /// Delete the oldest rolled log files beyond this many at startup
+    #[arg(long, value_name = "N")]
+    log_max_files: Option<usize>,
+
+    /// Print every exit code with its name and meaning, then exit
+    #[arg(long, hide = true)]
+    list_exit_codes: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Transcribe a short synthetic clip and report the realtime factor
+    /// and per-stage timings (of the --model), then exit
+    Selftest,
+    /// Check every cached model's size, GGML header and recorded SHA-256,
+    /// print a pass/fail table, and fail if any model is corrupt
+    Verify,
 }
 
 fn main() {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    cli.model_given = matches.value_source("model") == Some(ValueSource::CommandLine);
+    if cli.list_exit_codes {
+        print!("{}", ExitCode::list());
+        return;
+    }
+    apply_quantization(&mut cli.model, &cli.quantization);
+    if let Err(msg) = output::check_formats(&cli.format) {
+        Cli::command()
+            .error(clap::error::ErrorKind::ArgumentConflict, msg)
+            .exit();
+    }
+    if let Some(msg) = stdout_conflict(&cli) {
+        Cli::command()
+            .error(clap::error::ErrorKind::ArgumentConflict, msg)
+            .exit();
+    }
+    if cli.dump_audio.is_some() && cli.file.as_deref().is_some_and(Path::is_dir) {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--dump-audio writes a single file and cannot be used with a directory input",
+            )
+            .exit();
+    }
 
     let verbosity = if cli.quiet {
         Verbosity::Quiet
@@ -58,8 +393,17 @@ fn main() {
         Verbosity::Normal
     };
 
+    let log_file = logging::LogFileOptions {
+        path: cli.log_file.as_ref(),
+        rotation: match cli.log_rotation.as_str() {
+            "hourly" => LogRotation::Hourly,
+            "never" => LogRotation::Never,
+            _ => LogRotation::Daily,
+        },
+        max_files: cli.log_max_files,
+    };
     // _guard must live until program exit to flush the log file
-    let _guard = logging::init(verbosity, cli.log_file.as_ref());
+    let _guard = logging::init(verbosity, &log_file);
 
     log_system_info();
 
@@ -84,23 +428,65 @@ fn main() {
     }
 }
 
+/// Stdout takes a single format: reject several `--format` values when
+/// the transcript would go there, from any kind of input.
+fn stdout_conflict(cli: &Cli) -> Option<&'static str> {
+    if cli.format.len() < 2 {
+        return None;
+    }
+    let from_stdin = cli.file.as_deref().is_some_and(audio::is_stdin);
+    if cli.output.as_deref() == Some(Path::new(output::STDOUT_PATH)) {
+        Some("--output - writes a single format to stdout; give one --format")
+    } else if from_stdin && cli.output.is_none() {
+        Some("several --format values with stdin input need an explicit --output")
+    } else {
+        None
+    }
+}
+
 fn run_app(cli: Cli) -> Result<()> {
-    let audio_path = match cli.file {
-        Some(p) => p,
-        None => match pick_file_gui() {
-            Some(p) => p,
-            None => {
+    if let Some(Command::Selftest) = &cli.command {
+        let mut opts = build_options(&cli)?;
+        // Keep the timing to a single pass
+        opts.retry = false;
+        opts.checkpoint = false;
+        selftest::run(&cli.model, &opts)?;
+        return Ok(());
+    }
+    if let Some(Command::Verify) = &cli.command {
+        return verify_cache();
+    }
+
+    let audio_path = match &cli.file {
+        Some(p) => p.clone(),
+        None => loop {
+            if let Some(p) = pick_file_gui() {
+                break p;
+            }
+            // Only a double-click launch gets a second chance; scripted
+            // runs exit straight away
+            if std::env::args().len() != 1 || ask_picker_choice() == PickerChoice::Quit {
                 info!("No file selected.");
                 return Ok(());
             }
         },
     };
 
+    if audio::is_stdin(&audio_path) {
+        return run_stdin(&cli, &audio_path);
+    }
+
     let audio_path = std::fs::canonicalize(&audio_path).map_err(|e| AudioError::FileOpen {
         path: audio_path.display().to_string(),
         source: e,
     })?;
 
+    if audio_path.is_dir() {
+        let mut opts = build_options(&cli)?;
+        opts.path_base = audio_path.clone();
+        return run_batch(&cli, &audio_path, &opts);
+    }
+
     if !audio_path.is_file() {
         return Err(AudioError::NotAFile {
             path: audio_path.display().to_string(),
@@ -108,15 +494,13 @@ fn run_app(cli: Cli) -> Result<()> {
         .into());
     }
 
-    let output_path = cli.output.unwrap_or_else(|| {
-        let stem = audio_path.file_stem().unwrap_or_default();
-        let parent = audio_path
-            .parent()
-            .unwrap_or_else(|| std::path::Path::new("."));
-        parent.join(format!("{}_transcript.txt", stem.to_string_lossy()))
-    });
+    let opts = build_options(&cli)?;
+    let targets = match &cli.output {
+        Some(path) => output::targets_at(path, &cli.format),
+        None => batch::output_targets(&audio_path, None, &cli.format),
+    };
 
-    transcribe::run(&audio_path, &cli.model, &output_path)?;
+    process_file(&cli, &audio_path, &targets, &opts)?;
 
     // If launched with no args (double-click), wait before closing the console
     if std::env::args().len() == 1 {
@@ -128,6 +512,368 @@ fn run_app(cli: Cli) -> Result<()> {
     Ok(())
 }
 
+/// `verify` subcommand: check every model in the cache directory.
+fn verify_cache() -> Result<()> {
+    let dir = model::cache_dir()?;
+    let checks = model::verify_cache(&dir)?;
+    if checks.is_empty() {
+        println!("No models cached in {}", dir.display());
+        return Ok(());
+    }
+    print!("{}", model::verify_report(&checks));
+
+    let failed = checks.iter().filter(|c| !c.passed()).count();
+    info!(total = checks.len(), failed, "Cache verification complete");
+    if failed > 0 {
+        return Err(errors::ModelError::VerifyFailed {
+            failed,
+            total: checks.len(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Transcribe audio piped to stdin. With no input file name to derive
+/// from, output goes to `--output` or, by default, stdout.
+fn run_stdin(cli: &Cli, audio_path: &Path) -> Result<()> {
+    if cli.input_format.is_none() {
+        return Err(AudioError::StdinFormatRequired.into());
+    }
+    let opts = build_options(cli)?;
+    let targets = match &cli.output {
+        Some(path) => output::targets_at(path, &cli.format),
+        None => output::targets_at(Path::new(output::STDOUT_PATH), &cli.format),
+    };
+    process_file(cli, audio_path, &targets, &opts)?;
+    Ok(())
+}
+
+/// Transcribe every audio file in `dir`. Failures are logged and the
+/// batch continues; the first error is returned at the end.
+fn run_batch(cli: &Cli, dir: &Path, opts: &transcribe::Options) -> Result<()> {
+    let inputs = batch::collect_inputs(dir)?;
+    info!(dir = %dir.display(), files = inputs.len(), "Batch mode");
+
+    if let Some(out_dir) = &cli.output {
+        std::fs::create_dir_all(out_dir).map_err(|e| OutputError::FileCreate {
+            path: out_dir.display().to_string(),
+            source: e,
+        })?;
+    }
+
+    let jobs = cli.jobs as usize;
+    if jobs > 1 {
+        let total_mb = cli.jobs * model::context_memory_mb(&cli.model);
+        info!(jobs, total_mb, "Running batch in parallel");
+        if total_mb > PARALLEL_MEMORY_WARN_MB {
+            warn!(jobs, total_mb, "Parallel jobs may exhaust memory");
+            eprintln!(
+                "Warning: {jobs} jobs × whisper-{} need about {:.1} GB of memory",
+                cli.model,
+                total_mb as f64 / 1000.0
+            );
+        }
+        // Download or extract the model once so workers don't race on the cache
+        if !cli.stats_only {
+            model::resolve_model(&cli.model, &opts.download)?;
+        }
+    }
+
+    // Each job loads its own Whisper context inside transcribe::run
+    let process = |i: usize, input: &PathBuf| {
+        let name = input.file_name().unwrap_or_default().to_string_lossy();
+        status!("=== [{}/{}] {name} ===", i + 1, inputs.len());
+
+        let targets = batch::output_targets(input, cli.output.as_deref(), &cli.format);
+        let (status, metrics, error, err) = match process_file(cli, input, &targets, opts) {
+            Ok(Some(metrics)) => ("ok", Some(metrics), None, None),
+            Ok(None) => ("skipped", None, None, None),
+            Err(err) => {
+                error!(file = %input.display(), "Failed: {err:#}");
+                status!("Error: {err}");
+                ("failed", None, Some(format!("{err:#}")), Some(err))
+            }
+        };
+
+        let entry = batch::IndexEntry {
+            source: name.into_owned(),
+            output: targets
+                .iter()
+                .map(|t| t.path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(";"),
+            status,
+            metrics,
+            error,
+        };
+        (entry, err)
+    };
+    let results = batch::run_parallel(&inputs, jobs, |i, input| {
+        if jobs == 1 {
+            return process(i, input);
+        }
+        // One block per file, so parallel jobs don't interleave their lines
+        let (result, lines) = console::buffered(|| process(i, input));
+        eprint!("{lines}");
+        result
+    });
+
+    let mut first_err = None;
+    let mut failed = 0usize;
+    let mut skipped = 0usize;
+    let mut index = Vec::with_capacity(results.len());
+    for (entry, err) in results {
+        if let Some(err) = err {
+            failed += 1;
+            first_err.get_or_insert(err);
+        }
+        if entry.status == "skipped" {
+            skipped += 1;
+        }
+        index.push(entry);
+    }
+
+    info!(total = inputs.len(), skipped, failed, "Batch complete");
+
+    let index_written = cli.index.as_deref().map(|index_path| {
+        batch::write_index(index_path, &index, opts.write_mode)?;
+        info!(path = %index_path.display(), "Batch index written");
+        Ok::<_, OutputError>(())
+    });
+    eprintln!(
+        "Batch complete: {} succeeded, {skipped} skipped, {failed} failed",
+        inputs.len() - skipped - failed
+    );
+
+    // Reported after the summary so a bad --index path doesn't hide it
+    match (first_err, index_written) {
+        (Some(err), index_written) => {
+            if let Some(Err(index_err)) = index_written {
+                eprintln!("Error: {index_err}");
+            }
+            Err(err.context(format!("{failed} of {} files failed", inputs.len())))
+        }
+        (None, Some(Err(index_err))) => Err(index_err.into()),
+        (None, _) => Ok(()),
+    }
+}
+
+/// Run the selected mode on one audio file. Returns metrics when a
+/// transcript was written, `None` when the file was skipped or only
+/// inspected.
+fn process_file(
+    cli: &Cli,
+    audio_path: &Path,
+    targets: &[output::Target],
+    opts: &transcribe::Options,
+) -> Result<Option<transcribe::Metrics>> {
+    if cli.detect_language {
+        transcribe::detect_language(
+            audio_path,
+            &cli.model,
+            cli.input_format.as_deref(),
+            &opts.download,
+        )?;
+        return Ok(None);
+    }
+
+    if cli.dry_run {
+        transcribe::dry_run(
+            audio_path,
+            &cli.model,
+            cli.input_format.as_deref(),
+            &opts.download,
+        )?;
+        return Ok(None);
+    }
+
+    if cli.stats_only {
+        let (samples, info) = audio::load_audio(audio_path, cli.input_format.as_deref())?;
+        print!("{}", audio::SignalStats::measure(&samples).summary(&info));
+        return Ok(None);
+    }
+
+    let mut write_mode = opts.write_mode;
+    if cli.skip_existing {
+        match batch::resume_mode(audio_path, targets, write_mode) {
+            None => {
+                info!(output = %targets[0].path.display(), "Output is up to date — skipping");
+                status!("Skipping (up to date): {}", targets[0].path.display());
+                return Ok(None);
+            }
+            Some(mode) if mode != write_mode => {
+                debug!(output = %targets[0].path.display(), "Output is stale — reprocessing");
+                write_mode = mode;
+            }
+            Some(_) => {}
+        }
+    }
+    for target in targets {
+        output::check_writable_dir(&target.path)?;
+        output::check_target(&target.path, write_mode)?;
+    }
+
+    transcribe::run(audio_path, &cli.model, targets, write_mode, opts).map(Some)
+}
+
+/// Build the transcription options shared by every input file.
+fn build_options(cli: &Cli) -> Result<transcribe::Options> {
+    let write_mode = if cli.overwrite {
+        output::WriteMode::Overwrite
+    } else if cli.append {
+        output::WriteMode::Append
+    } else {
+        output::WriteMode::Protect
+    };
+
+    let header_template = cli
+        .header_template
+        .as_deref()
+        .map(header::HeaderTemplate::parse)
+        .transpose()?;
+    let text = output::TextOptions {
+        header: !cli.no_header && !header_template.as_ref().is_some_and(|t| t.is_empty()),
+        header_template,
+        timestamps: !cli.no_timestamps,
+        mark_pauses: cli.mark_pauses,
+        speakers: cli.mark_speakers.as_deref().map(|style| output::SpeakerMarks {
+            style: if style == "labels" {
+                output::SpeakerStyle::Labels
+            } else {
+                output::SpeakerStyle::Change
+            },
+            gap: cli.speaker_gap,
+        }),
+    };
+
+    let mask_words = match &cli.mask_words {
+        Some(path) => postprocess::load_word_list(path)?,
+        None => Vec::new(),
+    };
+
+    let fillers = match (&cli.filler_words, cli.remove_fillers) {
+        (Some(path), _) => Some(fillers::Fillers::load(path)?),
+        (None, true) => Some(fillers::Fillers::default()),
+        (None, false) => None,
+    };
+
+    let rules = match &cli.replacements {
+        Some(path) => postprocess::Rules::load(path)?,
+        None => postprocess::Rules::default(),
+    };
+
+    Ok(transcribe::Options {
+        input_format: cli.input_format.clone(),
+        segment_max_chars: cli.segment_max_chars.map(|n| n as usize),
+        max_line_length: cli.max_line_length,
+        write_mode,
+        text,
+        encoding: match cli.output_encoding.as_str() {
+            "utf8-bom" => output::Encoding::Utf8Bom,
+            _ => output::Encoding::Utf8,
+        },
+        path_style: match cli.path_style.as_str() {
+            "relative" => output::PathStyle::Relative,
+            "absolute" => output::PathStyle::Absolute,
+            _ => output::PathStyle::Name,
+        },
+        path_base: std::env::current_dir().unwrap_or_default(),
+        write_checksum: cli.write_checksum,
+        postprocess: postprocess::Config {
+            rules,
+            mask_words,
+            mask_token: cli.mask_token.clone(),
+            numbers: cli.numbers.as_deref().map(|n| match n {
+                "words" => numbers::NumberStyle::Words,
+                _ => numbers::NumberStyle::Digits,
+            }),
+            fillers,
+        },
+        confidence: segments::ConfidenceFilter {
+            drop_below: cli.drop_below,
+            flag_below: cli.flag_below,
+        },
+        max_compression_ratio: cli
+            .max_compression_ratio
+            .map(|r| r.unwrap_or(segments::DEFAULT_MAX_COMPRESSION_RATIO)),
+        suppress_blank: cli.suppress_blank,
+        suppress_nst: cli.suppress_nst,
+        decoding: profile::Overrides {
+            beam_size: cli.beam_size,
+            temperature_inc: cli.temperature_inc,
+            no_speech_thold: cli.no_speech_threshold,
+        },
+        language_hint: cli.language_hint.clone(),
+        tighten_timestamps: cli.tighten_timestamps,
+        download: model::DownloadOptions {
+            keep_partial: cli.keep_partial_on_failure,
+            explicit_model: cli.model_given,
+            base_url: cli.model_url.clone(),
+            auth: cli.auth_header.clone(),
+            ..model::DownloadOptions::from_secs(cli.connect_timeout, cli.download_timeout)
+        },
+        checkpoint: cli.checkpoint,
+        dump_audio: cli.dump_audio.clone(),
+        retry: !cli.no_retry,
+        // Several bars redrawing at once would garble the terminal
+        progress: !cli.quiet && cli.jobs <= 1,
+        vad: cli.vad.then_some(vad::VadConfig {
+            threshold_db: cli.vad_threshold,
+            min_speech_ms: cli.vad_min_speech,
+        }),
+        multilingual: cli.multilingual,
+    })
+}
+
+/// Replace `model` with its `--quantization` variant, exiting with a
+/// usage error if that variant isn't published for the size.
+fn apply_quantization(model: &mut String, quantization: &str) {
+    match model::variant_name(model, quantization) {
+        Ok(name) => *model = name,
+        Err(msg) => Cli::command()
+            .error(clap::error::ErrorKind::InvalidValue, msg)
+            .exit(),
+    }
+}
+
+/// Parse a probability threshold in `0.0..=1.0`.
+fn probability(s: &str) -> Result<f32, String> {
+    let p: f32 = s.parse().map_err(|_| format!("`{s}` is not a number"))?;
+    if (0.0..=1.0).contains(&p) {
+        Ok(p)
+    } else {
+        Err(format!("`{s}` is not between 0 and 1"))
+    }
+}
+
+/// Parse a ratio greater than zero.
+fn positive_ratio(s: &str) -> Result<f32, String> {
+    let r: f32 = s.parse().map_err(|_| format!("`{s}` is not a number"))?;
+    if r > 0.0 {
+        Ok(r)
+    } else {
+        Err(format!("`{s}` is not greater than 0"))
+    }
+}
+
+/// Parse an `http://` or `https://` URL.
+fn http_url(s: &str) -> Result<reqwest::Url, String> {
+    let url = reqwest::Url::parse(s).map_err(|e| format!("`{s}` is not a URL: {e}"))?;
+    if matches!(url.scheme(), "http" | "https") && url.has_host() {
+        Ok(url)
+    } else {
+        Err(format!("`{s}` is not an http(s) URL"))
+    }
+}
+
+/// Parse a language code Whisper knows, such as `tr` or `en`.
+fn language_code(s: &str) -> Result<&'static str, String> {
+    whisper_rs::get_lang_id(s)
+        .and_then(whisper_rs::get_lang_str)
+        .ok_or_else(|| format!("`{s}` is not a language code Whisper knows"))
+}
+
 /// Log system info at startup for diagnostics.
 fn log_system_info() {
     debug!(
@@ -141,15 +887,133 @@ fn log_system_info() {
     );
 }
 
+/// What to do after the file picker was cancelled.
+#[derive(Debug, PartialEq, Eq)]
+enum PickerChoice {
+    Retry,
+    Quit,
+}
+
+/// Map a console answer to a choice; `None` for anything unrecognised.
+/// An empty answer (just Enter) retries.
+fn parse_picker_choice(input: &str) -> Option<PickerChoice> {
+    let input = input.trim();
+    // Turkish casing for "HAYIR", plain casing for "QUIT"
+    [postprocess::turkish_lowercase(input), input.to_lowercase()]
+        .iter()
+        .find_map(|answer| match answer.as_str() {
+            "" | "r" | "retry" | "y" | "yes" | "e" | "evet" => Some(PickerChoice::Retry),
+            "q" | "quit" | "n" | "no" | "h" | "hayır" => Some(PickerChoice::Quit),
+            _ => None,
+        })
+}
+
+/// Ask on the console whether to reopen the file picker. End of input
+/// counts as quit.
+fn ask_picker_choice() -> PickerChoice {
+    loop {
+        eprint!("No file selected. Press Enter to choose again, or type q to quit: ");
+        let mut answer = String::new();
+        match std::io::stdin().read_line(&mut answer) {
+            Ok(0) | Err(_) => return PickerChoice::Quit,
+            Ok(_) => {}
+        }
+        if let Some(choice) = parse_picker_choice(&answer) {
+            return choice;
+        }
+    }
+}
+
 /// Open a native file-picker dialog.
 fn pick_file_gui() -> Option<PathBuf> {
     rfd::FileDialog::new()
         .set_title("Select an audio file to transcribe")
         .add_filter("MP3 files", &["mp3"])
-        .add_filter(
-            "Audio files",
-            &["mp3", "wav", "m4a", "ogg", "flac", "wma"],
-        )
+        .add_filter("Audio files", batch::AUDIO_EXTENSIONS)
         .add_filter("All files", &["*"])
         .pick_file()
 }
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picker_answers_map_to_retry_or_quit() {
+        for answer in ["\n", "r\n", "Retry", " y ", "evet\r\n", "EVET", "E"] {
+            assert_eq!(parse_picker_choice(answer), Some(PickerChoice::Retry), "{answer:?}");
+        }
+        for answer in ["q\n", "QUIT", "n", "hayır\n", "HAYIR", "H"] {
+            assert_eq!(parse_picker_choice(answer), Some(PickerChoice::Quit), "{answer:?}");
+        }
+        assert_eq!(parse_picker_choice("maybe"), None);
+    }
+
+    #[test]
+    fn mark_speakers_leaves_the_input_file_alone() {
+        let cli = Cli::try_parse_from(["tt", "--mark-speakers", "kayit.mp3"]).unwrap();
+        assert_eq!(cli.mark_speakers.as_deref(), Some("change"));
+        assert_eq!(cli.file.as_deref(), Some(Path::new("kayit.mp3")));
+
+        let cli = Cli::try_parse_from(["tt", "--mark-speakers=labels", "kayit.mp3"]).unwrap();
+        assert_eq!(cli.mark_speakers.as_deref(), Some("labels"));
+
+        assert!(Cli::try_parse_from(["tt", "--speaker-gap", "3", "kayit.mp3"]).is_err());
+    }
+
+    #[test]
+    fn max_compression_ratio_value_needs_equals() {
+        let cli = Cli::try_parse_from(["tt", "--max-compression-ratio", "kayit.mp3"]).unwrap();
+        assert_eq!(cli.max_compression_ratio, Some(None));
+        assert_eq!(cli.file.as_deref(), Some(Path::new("kayit.mp3")));
+
+        let cli = Cli::try_parse_from(["tt", "--max-compression-ratio=3"]).unwrap();
+        assert_eq!(cli.max_compression_ratio, Some(Some(3.0)));
+
+        assert!(Cli::try_parse_from(["tt", "--max-compression-ratio=0"]).is_err());
+        assert!(Cli::try_parse_from(["tt", "--max-compression-ratio=-1"]).is_err());
+    }
+
+    #[test]
+    fn jobs_must_be_positive() {
+        let cli = Cli::try_parse_from(["tt", "--jobs", "4", "kayitlar"]).unwrap();
+        assert_eq!(cli.jobs, 4);
+        assert_eq!(Cli::try_parse_from(["tt", "kayitlar"]).unwrap().jobs, 1);
+        assert!(Cli::try_parse_from(["tt", "--jobs", "0", "kayitlar"]).is_err());
+    }
+
+    #[test]
+    fn temperature_step_between_zero_and_one() {
+        let cli = Cli::try_parse_from(["tt", "--temperature-inc", "0.4", "kayit.mp3"]).unwrap();
+        assert_eq!(cli.temperature_inc, Some(0.4));
+        for step in ["-0.2", "1.5", "sıcak"] {
+            assert!(Cli::try_parse_from(["tt", "--temperature-inc", step, "kayit.mp3"]).is_err());
+        }
+    }
+
+    #[test]
+    fn several_formats_rejected_on_stdout() {
+        let conflict = |args: &[&str]| stdout_conflict(&Cli::try_parse_from(args).unwrap());
+        assert!(conflict(&["tt", "kayit.mp3", "-o", "-", "--format", "txt,srt"]).is_some());
+        assert!(conflict(&["tt", "kayitlar", "-o", "-", "--format", "txt,json"]).is_some());
+        assert!(conflict(&["tt", "-", "--format", "txt,srt"]).is_some());
+        assert!(conflict(&["tt", "-", "-o", "out.txt", "--format", "txt,srt"]).is_none());
+        assert!(conflict(&["tt", "kayit.mp3", "-o", "-", "--format", "srt"]).is_none());
+        assert!(conflict(&["tt", "kayit.mp3", "--format", "txt,srt"]).is_none());
+    }
+
+    #[test]
+    fn selftest_uses_the_global_model() {
+        let orders = [
+            ["tt", "selftest", "--model", "tiny"],
+            ["tt", "--model", "tiny", "selftest"],
+        ];
+        for args in orders {
+            let cli = Cli::try_parse_from(args).unwrap();
+            assert!(matches!(cli.command, Some(Command::Selftest)));
+            assert_eq!(cli.model, "tiny");
+        }
+    }
+}
@@ -1,11 +1,15 @@
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
 use anyhow::Result;
 use indicatif::{ProgressBar, ProgressStyle};
+use reqwest::header::{HeaderName, HeaderValue};
+use sha2::{Digest, Sha256};
 use tracing::{debug, info, warn};
 
+use crate::console::status;
 use crate::errors::ModelError;
 
 /// Maximum number of download attempts.
@@ -15,12 +19,16 @@ const MAX_RETRIES: u32 = 3;
 const BACKOFF_SECS: &[u64] = &[1, 2, 4];
 
 /// HTTP connect timeout.
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// HTTP total download timeout (10 minutes — large models).
-const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+pub const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
 
-/// Minimum expected model file sizes in bytes (approximate).
+/// Base URL the GGML models are downloaded from.
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// Minimum expected model file sizes in bytes (approximate), keyed by
+/// model name (size, plus `-q…` for quantized variants).
 fn min_model_size(model: &str) -> u64 {
     match model {
         "tiny" => 50_000_000,       // ~75 MB
@@ -28,47 +36,247 @@ fn min_model_size(model: &str) -> u64 {
         "small" => 300_000_000,     // ~500 MB
         "medium" => 1_000_000_000,  // ~1.5 GB
         "large-v3" => 2_000_000_000, // ~3 GB
+        "tiny-q5_1" => 20_000_000,        // ~31 MB
+        "tiny-q8_0" => 30_000_000,        // ~42 MB
+        "base-q5_1" => 40_000_000,        // ~57 MB
+        "base-q8_0" => 50_000_000,        // ~78 MB
+        "small-q5_1" => 120_000_000,      // ~181 MB
+        "small-q8_0" => 170_000_000,      // ~252 MB
+        "medium-q5_0" => 350_000_000,     // ~514 MB
+        "medium-q8_0" => 500_000_000,     // ~785 MB
+        "large-v3-q5_0" => 700_000_000,   // ~1.1 GB
         _ => 0,
     }
 }
 
-/// Check for a bundled model next to the executable, then the cache.
-/// Downloads the GGML model from HuggingFace if not found.
+/// Quantizations published upstream for each model size.
+fn quantizations(size: &str) -> &'static [&'static str] {
+    match size {
+        "tiny" | "base" | "small" => &["q5_1", "q8_0"],
+        "medium" => &["q5_0", "q8_0"],
+        "large-v3" => &["q5_0"],
+        _ => &[],
+    }
+}
+
+/// Model name for `size` with `quantization` (`none` for the full
+/// model), e.g. `medium-q5_0`. Fails if no such variant is published.
+pub fn variant_name(size: &str, quantization: &str) -> Result<String, String> {
+    if quantization == "none" {
+        return Ok(size.to_string());
+    }
+    let available = quantizations(size);
+    if !available.contains(&quantization) {
+        return Err(format!(
+            "no {quantization} variant of the {size} model (available: {})",
+            available.join(", ")
+        ));
+    }
+    Ok(format!("{size}-{quantization}"))
+}
+
+/// Model size without any quantization suffix (`medium-q5_0` → `medium`).
+pub(crate) fn base_size(model: &str) -> &str {
+    model.split_once("-q").map_or(model, |(size, _)| size)
+}
+
+/// Approximate memory used by one loaded Whisper context, in megabytes
+/// (whisper.cpp figures). Quantized variants use the full model's figure,
+/// which overestimates.
+pub fn context_memory_mb(model: &str) -> u64 {
+    match base_size(model) {
+        "tiny" => 390,
+        "base" => 500,
+        "small" => 1_000,
+        "medium" => 2_600,
+        "large-v3" => 4_700,
+        _ => 0,
+    }
+}
+
+/// Typical CPU realtime factor (inference time ÷ audio length), used
+/// for the ETA until inference reports its own progress.
+pub fn typical_realtime_factor(model: &str) -> f64 {
+    match base_size(model) {
+        "tiny" => 0.05,
+        "base" => 0.1,
+        "small" => 0.3,
+        "medium" => 0.8,
+        "large-v3" => 1.6,
+        _ => 1.0,
+    }
+}
+
+/// GGML model embedded at compile time (`embed-model` feature). The file is
+/// taken from the `TRANSCRIBER_EMBED_MODEL` environment variable at build time.
+#[cfg(feature = "embed-model")]
+static EMBEDDED_MODEL: Option<&[u8]> = Some(include_bytes!(env!("TRANSCRIBER_EMBED_MODEL")));
+
+#[cfg(not(feature = "embed-model"))]
+static EMBEDDED_MODEL: Option<&[u8]> = None;
+
+/// Build-time path of the embedded model, whose `ggml-<model>.bin` file
+/// name tells which model it is.
+#[cfg(feature = "embed-model")]
+const EMBEDDED_MODEL_PATH: Option<&str> = Some(env!("TRANSCRIBER_EMBED_MODEL"));
+
+#[cfg(not(feature = "embed-model"))]
+const EMBEDDED_MODEL_PATH: Option<&str> = None;
+
+/// Tells apart extraction temp files written concurrently by one process.
+static NEXT_EXTRACTION: AtomicUsize = AtomicUsize::new(0);
+
+/// How the model is found and downloaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DownloadOptions {
+    /// Connect timeout; `None` waits indefinitely
+    pub connect_timeout: Option<Duration>,
+    /// Timeout for the whole download; `None` for no limit
+    pub download_timeout: Option<Duration>,
+    /// Keep the `.part` file of a failed attempt instead of deleting it
+    /// (`--keep-partial-on-failure`)
+    pub keep_partial: bool,
+    /// `--model` was given on the command line, so an embedded or bundled
+    /// model of another (or unknown) size is not used
+    pub explicit_model: bool,
+    /// Model host to download from instead of HuggingFace (`--model-url`)
+    pub base_url: Option<reqwest::Url>,
+    /// Header sent with requests to `base_url`, for authenticated model
+    /// hosts (`--auth-header`)
+    pub auth: Option<AuthHeader>,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Some(CONNECT_TIMEOUT),
+            download_timeout: Some(DOWNLOAD_TIMEOUT),
+            keep_partial: false,
+            explicit_model: false,
+            base_url: None,
+            auth: None,
+        }
+    }
+}
+
+impl DownloadOptions {
+    /// Timeouts given in seconds, where 0 means no timeout.
+    pub fn from_secs(connect_secs: u64, download_secs: u64) -> Self {
+        let timeout = |secs| (secs > 0).then(|| Duration::from_secs(secs));
+        Self {
+            connect_timeout: timeout(connect_secs),
+            download_timeout: timeout(download_secs),
+            ..Self::default()
+        }
+    }
+}
+
+/// Placeholder logged instead of a secret header value.
+const REDACTED: &str = "<redacted>";
+
+/// An HTTP header such as `Authorization: Bearer …`. The value is a
+/// secret: `Debug` and `Display` show only the header name.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AuthHeader {
+    name: HeaderName,
+    value: HeaderValue,
+}
+
+impl std::str::FromStr for AuthHeader {
+    type Err = String;
+
+    /// Parse `Name: value`. Errors never echo the value.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (name, value) = s
+            .split_once(':')
+            .ok_or("expected `Name: value`, e.g. `Authorization: Bearer <token>`")?;
+        let name = HeaderName::from_bytes(name.trim().as_bytes())
+            .map_err(|_| format!("`{}` is not a valid header name", name.trim()))?;
+        let mut value = HeaderValue::from_str(value.trim())
+            .map_err(|_| format!("value of the {name} header contains invalid characters"))?;
+        if value.is_empty() {
+            return Err(format!("value of the {name} header is empty"));
+        }
+        value.set_sensitive(true);
+        Ok(Self { name, value })
+    }
+}
+
+impl std::fmt::Display for AuthHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {REDACTED}", self.name)
+    }
+}
+
+impl std::fmt::Debug for AuthHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AuthHeader({self})")
+    }
+}
+
+/// The auth header to send with a request to `url`, if any. The header
+/// only ever goes to the host given with `--model-url`; without one,
+/// downloads go to the public HuggingFace host and it is withheld.
+fn auth_for<'a>(url: &str, options: &'a DownloadOptions) -> Option<&'a AuthHeader> {
+    let auth = options.auth.as_ref()?;
+    let Some(base_url) = &options.base_url else {
+        warn!(header = %auth, "No --model-url set — auth header not sent to HuggingFace");
+        return None;
+    };
+    let request_url = reqwest::Url::parse(url).ok();
+    if request_url.as_ref().and_then(|u| u.host_str()) != base_url.host_str() {
+        warn!(header = %auth, url, "Not the --model-url host — auth header not sent");
+        return None;
+    }
+    info!(header = %auth, "Sending auth header with model download");
+    Some(auth)
+}
+
+/// Check for an embedded model, then a bundled model next to the
+/// executable, then the cache. Downloads the GGML model from HuggingFace
+/// if not found.
 #[tracing::instrument(skip_all, fields(model_size = size))]
-pub fn resolve_model(size: &str) -> Result<(PathBuf, bool)> {
-    // 1. Bundled model next to the binary
-    let exe_dir = std::env::current_exe()
-        .ok()
-        .and_then(|p| p.parent().map(|d| d.to_path_buf()))
-        .unwrap_or_else(|| PathBuf::from("."));
+pub fn resolve_model(size: &str, download: &DownloadOptions) -> Result<(PathBuf, bool)> {
+    // 0. Model embedded in the binary, unless --model asked for another
+    let embedded_name = EMBEDDED_MODEL_PATH.and_then(embedded_model_name);
+    if EMBEDDED_MODEL_PATH.is_some()
+        && !embedded_serves(embedded_name, size, download.explicit_model)
+    {
+        info!(
+            embedded = embedded_name.unwrap_or("unknown"),
+            requested = size,
+            "--model given — not using the embedded model"
+        );
+    } else if let Some(path) = extract_embedded_model()? {
+        info!(path = %path.display(), "Using embedded model");
+        return Ok((path, true));
+    }
 
-    let bundled = exe_dir.join("model").join(model_filename(size));
+    // 1. Bundled model next to the binary
+    let bundled_dir = bundled_dir();
+    let bundled = bundled_dir.join(model_filename(size));
     if bundled.is_file() {
         info!(path = %bundled.display(), "Using bundled model");
         return Ok((bundled, true));
     }
 
-    // Also check for a generic "model/model.bin" (legacy layout)
-    let bundled_legacy = exe_dir.join("model").join("model.bin");
+    // Also check for a generic "model/model.bin" (legacy layout), whose
+    // size is unknown, unless --model asked for a specific one
+    let bundled_legacy = bundled_dir.join(BUNDLED_LEGACY);
     if bundled_legacy.is_file() {
-        info!(path = %bundled_legacy.display(), "Using bundled model (legacy layout)");
-        return Ok((bundled_legacy, true));
+        if !download.explicit_model {
+            info!(path = %bundled_legacy.display(), "Using bundled model (legacy layout)");
+            return Ok((bundled_legacy, true));
+        }
+        info!(path = %bundled_legacy.display(), "--model given — skipping legacy bundled model");
     }
 
     debug!("No bundled model found, checking cache");
 
     // 2. Cached model in ~/.cache/whisper-models/
-    let cache_dir = dirs::home_dir()
-        .map(|h| h.join(".cache").join("whisper-models"))
-        .ok_or(ModelError::NoCacheDir)?;
-
-    std::fs::create_dir_all(&cache_dir).map_err(|e| ModelError::CacheDirCreation {
-        path: cache_dir.display().to_string(),
-        source: e,
-    })?;
-
-    let cached = cache_dir.join(model_filename(size));
-    if cached.is_file() {
+    let cache_dir = cache_dir()?;
+    migrate_flat_cache_once(&cache_dir);
+    if let Some(cached) = find_cached(&cache_dir, size) {
         info!(path = %cached.display(), "Using cached model");
 
         // Validate cached file size
@@ -91,31 +299,121 @@ pub fn resolve_model(size: &str) -> Result<(PathBuf, bool)> {
 
     // 3. Download with retry
     debug!("Model not in cache, downloading");
-    download_model_with_retry(size, &cached)?;
-    Ok((cached, false))
+    let dest = nested_path(&cache_dir, size);
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| ModelError::CacheDirCreation {
+            path: parent.display().to_string(),
+            source: e,
+        })?;
+    }
+    download_model_with_retry(size, &dest, download)?;
+    Ok((dest, false))
+}
+
+/// File name of a bundled model of unspecified size (legacy layout).
+const BUNDLED_LEGACY: &str = "model.bin";
+
+/// `model/` directory next to the executable, where bundled models live.
+fn bundled_dir() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|d| d.to_path_buf()))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("model")
+}
+
+/// The model cache directory (`~/.cache/whisper-models`), created if
+/// missing.
+pub fn cache_dir() -> Result<PathBuf, ModelError> {
+    let cache_dir = dirs::home_dir()
+        .map(|h| h.join(".cache").join("whisper-models"))
+        .ok_or(ModelError::NoCacheDir)?;
+
+    std::fs::create_dir_all(&cache_dir).map_err(|e| ModelError::CacheDirCreation {
+        path: cache_dir.display().to_string(),
+        source: e,
+    })?;
+    Ok(cache_dir)
+}
+
+/// Model name of an embedded `ggml-<model>.bin` build path.
+fn embedded_model_name(path: &str) -> Option<&str> {
+    Path::new(path).file_stem()?.to_str()?.strip_prefix("ggml-")
+}
+
+/// Whether the embedded model (`embedded`, if its name is known) is used
+/// for a request for `size`: always, unless `--model` asked for another.
+fn embedded_serves(embedded: Option<&str>, size: &str, explicit_model: bool) -> bool {
+    !explicit_model || embedded == Some(size)
 }
 
-fn model_filename(size: &str) -> String {
-    format!("ggml-{size}.bin")
+/// Write the embedded model to the per-user cache
+/// (`~/.cache/turkish-transcriber`), since `WhisperContext` needs a
+/// filesystem path. The file is named after the model's SHA-256, so only
+/// an extraction of this exact model is reused. Returns `None` when no
+/// model is embedded.
+fn extract_embedded_model() -> Result<Option<PathBuf>> {
+    let Some(bytes) = EMBEDDED_MODEL else {
+        return Ok(None);
+    };
+
+    let dir = dirs::home_dir()
+        .map(|h| h.join(".cache").join("turkish-transcriber"))
+        .ok_or(ModelError::NoCacheDir)?;
+    std::fs::create_dir_all(&dir).map_err(|e| ModelError::CacheDirCreation {
+        path: dir.display().to_string(),
+        source: e,
+    })?;
+
+    let hash = hex(Sha256::digest(bytes).as_slice());
+    let path = dir.join(format!("embedded-{}.bin", &hash[..16]));
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() == bytes.len() as u64) {
+        debug!(path = %path.display(), "Embedded model already extracted");
+        return Ok(Some(path));
+    }
+
+    debug!(path = %path.display(), size = bytes.len(), "Extracting embedded model");
+    // A name no other process or thread writes to, renamed into place
+    let tmp = dir.join(format!(
+        "embedded-{}.{}.{}.part",
+        &hash[..16],
+        std::process::id(),
+        NEXT_EXTRACTION.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&tmp, bytes).map_err(|e| ModelError::CacheDirCreation {
+        path: tmp.display().to_string(),
+        source: e,
+    })?;
+    std::fs::rename(&tmp, &path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        ModelError::RenameFailed(e.to_string())
+    })?;
+
+    Ok(Some(path))
+}
+
+fn model_filename(model: &str) -> String {
+    format!("ggml-{model}.bin")
+}
+
+/// Download URL of `model`, from `base_url` (`--model-url`) or HuggingFace.
+fn model_url(model: &str, base_url: Option<&reqwest::Url>) -> String {
+    let base = base_url.map_or(MODEL_BASE_URL, |u| u.as_str().trim_end_matches('/'));
+    format!("{base}/{}", model_filename(model))
 }
 
 /// Download with exponential backoff retry.
-fn download_model_with_retry(size: &str, dest: &Path) -> Result<()> {
+fn download_model_with_retry(size: &str, dest: &Path, options: &DownloadOptions) -> Result<()> {
     let mut last_err = String::new();
 
     for attempt in 1..=MAX_RETRIES {
-        match download_model(size, dest) {
+        match download_model(size, dest, options) {
             Ok(()) => return Ok(()),
             Err(e) => {
                 last_err = format!("{e:#}");
                 warn!(attempt, max = MAX_RETRIES, error = %last_err, "Download attempt failed");
 
-                // Clean up partial file
-                let tmp = dest.with_extension("part");
-                if tmp.exists() {
-                    debug!(path = %tmp.display(), "Cleaning up temp file");
-                    let _ = std::fs::remove_file(&tmp);
-                }
+                discard_partial(dest, attempt, options.keep_partial);
 
                 if attempt < MAX_RETRIES {
                     let delay = BACKOFF_SECS
@@ -136,28 +434,73 @@ fn download_model_with_retry(size: &str, dest: &Path) -> Result<()> {
     .into())
 }
 
+/// Remove the `.part` file left by a failed attempt, or with
+/// `keep` rename it to `.part.<attempt>` so it can be inspected. Returns
+/// the preserved path.
+fn discard_partial(dest: &Path, attempt: u32, keep: bool) -> Option<PathBuf> {
+    let tmp = dest.with_extension("part");
+    if !tmp.exists() {
+        return None;
+    }
+    if !keep {
+        debug!(path = %tmp.display(), "Cleaning up temp file");
+        let _ = std::fs::remove_file(&tmp);
+        return None;
+    }
+    let kept = dest.with_extension(format!("part.{attempt}"));
+    match std::fs::rename(&tmp, &kept) {
+        Ok(()) => {
+            info!(path = %kept.display(), "Kept partial download");
+            status!("Partial download kept at {}", kept.display());
+            Some(kept)
+        }
+        Err(e) => {
+            warn!(path = %tmp.display(), error = %e, "Cannot keep partial download");
+            None
+        }
+    }
+}
+
+/// The timeout that elapsed: while `connecting` the shorter of the two
+/// limits, otherwise the download limit. No duration when none was set.
+fn timeout_error(connecting: bool, options: &DownloadOptions) -> ModelError {
+    let connect = options.connect_timeout.filter(|_| connecting);
+    match (connect, options.download_timeout) {
+        (Some(c), d) if d.is_none_or(|d| d >= c) => ModelError::Timeout {
+            stage: "Connecting to the model server",
+            seconds: Some(c.as_secs()),
+        },
+        (_, d) => ModelError::Timeout {
+            stage: "Download",
+            seconds: d.map(|d| d.as_secs()),
+        },
+    }
+}
+
 #[tracing::instrument(skip_all, fields(model_size = size))]
-fn download_model(size: &str, dest: &Path) -> Result<()> {
-    let url = format!(
-        "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-{size}.bin"
-    );
+fn download_model(size: &str, dest: &Path, options: &DownloadOptions) -> Result<()> {
+    let url = model_url(size, options.base_url.as_ref());
 
     info!(url = %url, "Downloading model");
 
+    // Both timeouts are always set: the blocking client otherwise has its
+    // own 30 s default
     let client = reqwest::blocking::Client::builder()
-        .connect_timeout(CONNECT_TIMEOUT)
-        .timeout(DOWNLOAD_TIMEOUT)
+        .connect_timeout(options.connect_timeout)
+        .timeout(options.download_timeout)
         .build()
         .map_err(|e| ModelError::DownloadFailed {
             attempts: 1,
             reason: format!("Cannot build HTTP client: {e}"),
         })?;
 
-    let resp = client.get(&url).send().map_err(|e| {
+    let mut request = client.get(&url);
+    if let Some(auth) = auth_for(&url, options) {
+        request = request.header(auth.name.clone(), auth.value.clone());
+    }
+    let resp = request.send().map_err(|e| {
         if e.is_timeout() {
-            ModelError::Timeout {
-                seconds: DOWNLOAD_TIMEOUT.as_secs(),
-            }
+            timeout_error(e.is_connect(), options)
         } else {
             ModelError::DownloadFailed {
                 attempts: 1,
@@ -192,9 +535,15 @@ fn download_model(size: &str, dest: &Path) -> Result<()> {
     })?;
 
     let mut reader = pb.wrap_read(resp);
-    std::io::copy(&mut reader, &mut file).map_err(|e| ModelError::DownloadFailed {
-        attempts: 1,
-        reason: format!("I/O error during download: {e}"),
+    std::io::copy(&mut reader, &mut file).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::TimedOut {
+            timeout_error(false, options)
+        } else {
+            ModelError::DownloadFailed {
+                attempts: 1,
+                reason: format!("I/O error during download: {e}"),
+            }
+        }
     })?;
     file.flush().map_err(|e| ModelError::DownloadFailed {
         attempts: 1,
@@ -210,7 +559,7 @@ fn download_model(size: &str, dest: &Path) -> Result<()> {
             .map(|m| m.len())
             .unwrap_or(0);
         if actual < min {
-            let _ = std::fs::remove_file(&tmp);
+            // The retry loop removes or keeps the .part file
             return Err(ModelError::FileTooSmall {
                 size: actual,
                 expected: min,
@@ -222,5 +571,715 @@ fn download_model(size: &str, dest: &Path) -> Result<()> {
 
     std::fs::rename(&tmp, dest).map_err(|e| ModelError::RenameFailed(e.to_string()))?;
     info!(path = %dest.display(), "Model saved");
+
+    // Record the checksum so `verify` can detect later corruption
+    let file_name = dest.file_name().unwrap_or_default().to_string_lossy();
+    let recorded = sha256_hex(dest)
+        .and_then(|hash| std::fs::write(checksum_path(dest), format!("{hash}  {file_name}\n")));
+    if let Err(e) = recorded {
+        warn!("Cannot record model checksum: {e}");
+    }
     Ok(())
 }
+
+// ── Cache layout ────────────────────────────────────────────────────
+
+/// Variant name of a full (unquantized, f16) model in the nested layout.
+const FULL_VARIANT: &str = "f16";
+
+/// Cache path of `model`: `<cache>/<size>/<variant>.bin`, where the
+/// variant is the quantization (`medium/q5_0.bin`) or `f16` for the full
+/// model.
+fn nested_path(cache: &Path, model: &str) -> PathBuf {
+    let size = base_size(model);
+    let variant = model
+        .strip_prefix(size)
+        .and_then(|q| q.strip_prefix('-'))
+        .unwrap_or(FULL_VARIANT);
+    cache.join(size).join(format!("{variant}.bin"))
+}
+
+/// Model name of a cached file in either layout: `medium/q5_0.bin` and
+/// the legacy flat `ggml-medium-q5_0.bin` are both `medium-q5_0`.
+fn cached_model_name(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    if let Some(name) = stem.strip_prefix("ggml-") {
+        return Some(name.to_string());
+    }
+    let size = path.parent()?.file_name()?.to_str()?;
+    Some(if stem == FULL_VARIANT {
+        size.to_string()
+    } else {
+        format!("{size}-{stem}")
+    })
+}
+
+/// The cached copy of `model`, looking in the nested layout first and
+/// then in the legacy flat one.
+fn find_cached(cache: &Path, model: &str) -> Option<PathBuf> {
+    [nested_path(cache, model), cache.join(model_filename(model))]
+        .into_iter()
+        .find(|p| p.is_file())
+}
+
+/// Marker file left in the cache once the flat layout has been migrated.
+const MIGRATED_MARKER: &str = ".nested-layout";
+
+/// Migrate the flat cache layout once. Whichever run creates the marker
+/// does the migration; later and concurrent runs skip it, and the flat
+/// layout is still searched for anything left behind.
+fn migrate_flat_cache_once(cache: &Path) {
+    let claimed = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(cache.join(MIGRATED_MARKER));
+    match claimed {
+        Ok(_) => {
+            migrate_flat_cache(cache);
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+        Err(e) => debug!(error = %e, "Cannot create cache migration marker — not migrating"),
+    }
+}
+
+/// Whether `model` names a published model, such as `medium` or
+/// `medium-q5_0`.
+fn is_known_model(model: &str) -> bool {
+    let size = base_size(model);
+    match model.strip_prefix(size).and_then(|q| q.strip_prefix('-')) {
+        Some(quantization) => quantizations(size).contains(&quantization),
+        None => model == size && !quantizations(size).is_empty(),
+    }
+}
+
+/// Move models cached in the legacy flat layout (`ggml-<model>.bin`),
+/// with their checksum files, into the nested layout. Only files named
+/// after a published model are moved, and a model already present in the
+/// nested layout is left where it is. Returns the number of models moved.
+fn migrate_flat_cache(cache: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(cache) else {
+        return 0;
+    };
+    let mut moved = 0;
+    for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+        let is_flat_model = path.is_file()
+            && path.extension().is_some_and(|e| e == "bin")
+            && path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with("ggml-"));
+        let known = cached_model_name(&path).filter(|m| is_flat_model && is_known_model(m));
+        let Some(model) = known else {
+            continue;
+        };
+        let dest = nested_path(cache, &model);
+        if dest.exists() {
+            debug!(path = %path.display(), "Nested copy exists — leaving flat model in place");
+            continue;
+        }
+        let result = dest
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::rename(&path, &dest));
+        if let Err(e) = result {
+            warn!(path = %path.display(), error = %e, "Cannot migrate cached model");
+            continue;
+        }
+        // Re-record the checksum under the new file name for `sha256sum -c`
+        let old_checksum = checksum_path(&path);
+        if let Ok(recorded) = std::fs::read_to_string(&old_checksum) {
+            if let Some(hash) = recorded.split_whitespace().next() {
+                let file_name = dest.file_name().unwrap_or_default().to_string_lossy();
+                let _ = std::fs::write(checksum_path(&dest), format!("{hash}  {file_name}\n"));
+            }
+            let _ = std::fs::remove_file(&old_checksum);
+        }
+        info!(from = %path.display(), to = %dest.display(), "Migrated cached model");
+        moved += 1;
+    }
+    moved
+}
+
+/// `*.bin` files directly in `dir`.
+fn bin_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    Ok(std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "bin"))
+        .collect())
+}
+
+/// Model files in `cache` and its per-size subdirectories, sorted by path.
+fn cached_files(cache: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut models = bin_files(cache)?;
+    for entry in std::fs::read_dir(cache)?.flatten() {
+        if entry.path().is_dir() {
+            models.extend(bin_files(&entry.path()).unwrap_or_default());
+        }
+    }
+    models.sort();
+    Ok(models)
+}
+
+// ── Available models ────────────────────────────────────────────────
+
+/// A model file that can be loaded without downloading anything.
+#[derive(Debug, PartialEq, Eq)]
+pub struct AvailableModel {
+    /// Model name as given to `--model`/`--quantization` (`medium-q5_0`),
+    /// or `None` for a legacy bundled `model.bin` of unknown size
+    pub name: Option<String>,
+    pub path: PathBuf,
+    pub bundled: bool,
+}
+
+/// Models in `bundled` (the `model/` directory next to the binary) and
+/// in `cache`, bundled ones first. Missing directories contribute nothing.
+pub fn list_models(bundled: &Path, cache: &Path) -> Vec<AvailableModel> {
+    let mut bundled_files = bin_files(bundled).unwrap_or_default();
+    bundled_files.sort();
+    let bundled_models = bundled_files.into_iter().map(|path| AvailableModel {
+        name: path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.strip_prefix("ggml-"))
+            .map(str::to_string),
+        path,
+        bundled: true,
+    });
+    let cached_models = cached_files(cache)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|path| AvailableModel {
+            name: cached_model_name(&path),
+            path,
+            bundled: false,
+        });
+    bundled_models.chain(cached_models).collect()
+}
+
+/// Bundled and cached models on this machine.
+pub fn available_models() -> Vec<AvailableModel> {
+    let cache = cache_dir().unwrap_or_default();
+    list_models(&bundled_dir(), &cache)
+}
+
+/// Note appended to a model load failure: the other models that could
+/// be used instead of `failed`, and how to select one.
+pub fn available_models_note(models: &[AvailableModel], failed: &Path) -> String {
+    let others: Vec<&AvailableModel> = models.iter().filter(|m| m.path != failed).collect();
+    if others.is_empty() {
+        return "\nNo other bundled or cached models found; \
+                run with network access to download one."
+            .to_string();
+    }
+    let mut note = String::from("\nAvailable models:");
+    for model in others {
+        let source = if model.bundled { "bundled" } else { "cached" };
+        let name = model.name.as_deref().unwrap_or("(unknown size)");
+        note.push_str(&format!(
+            "\n  {name:<16} {source:<8} {}",
+            model.path.display()
+        ));
+    }
+    note.push_str(
+        "\nSelect one with --model <size> [--quantization <q>]; an explicit --model \
+         skips an embedded model of another size and a bundled model.bin.",
+    );
+    note
+}
+
+// ── Cache verification ──────────────────────────────────────────────
+
+/// First four bytes of a GGML model file (`0x67676d6c`, little-endian).
+const GGML_MAGIC: [u8; 4] = *b"lmgg";
+
+/// Outcome of comparing a model with its recorded SHA-256.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChecksumStatus {
+    Match,
+    Mismatch,
+    /// No `.sha256` file next to the model (e.g. copied in by hand)
+    NotRecorded,
+}
+
+/// Verification result for one cached model file.
+#[derive(Debug)]
+pub struct ModelCheck {
+    pub file_name: String,
+    pub size: u64,
+    pub size_ok: bool,
+    pub magic_ok: bool,
+    pub checksum: ChecksumStatus,
+}
+
+impl ModelCheck {
+    /// A missing checksum isn't a failure: models cached before checksums
+    /// were recorded can't have one.
+    pub fn passed(&self) -> bool {
+        self.size_ok && self.magic_ok && self.checksum != ChecksumStatus::Mismatch
+    }
+}
+
+/// `<model>.sha256` sidecar path, in the format read by `sha256sum -c`.
+fn checksum_path(model: &Path) -> PathBuf {
+    let file_name = model.file_name().unwrap_or_default().to_string_lossy();
+    model.with_file_name(format!("{file_name}.sha256"))
+}
+
+pub(crate) fn sha256_hex(path: &Path) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(hex(hasher.finalize().as_slice()))
+}
+
+/// Lowercase hex encoding of `bytes`.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Check one model file's size threshold, magic bytes and checksum.
+/// `file_name` is the path relative to the cache directory.
+fn check_model(dir: &Path, path: &Path) -> ModelCheck {
+    let file_name = path
+        .strip_prefix(dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned();
+    let name = cached_model_name(path).unwrap_or_default();
+    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+
+    let mut magic = [0u8; 4];
+    let magic_ok = std::fs::File::open(path)
+        .and_then(|mut f| std::io::Read::read_exact(&mut f, &mut magic))
+        .is_ok()
+        && magic == GGML_MAGIC;
+
+    let recorded = std::fs::read_to_string(checksum_path(path)).ok();
+    let checksum = match recorded.as_deref().and_then(|s| s.split_whitespace().next()) {
+        None => ChecksumStatus::NotRecorded,
+        Some(expected) => match sha256_hex(path) {
+            Ok(actual) if actual.eq_ignore_ascii_case(expected) => ChecksumStatus::Match,
+            _ => ChecksumStatus::Mismatch,
+        },
+    };
+
+    ModelCheck {
+        size_ok: size > 0 && size >= min_model_size(&name),
+        file_name,
+        size,
+        magic_ok,
+        checksum,
+    }
+}
+
+/// Verify every `*.bin` model in `dir` and its per-size subdirectories,
+/// sorted by path.
+pub fn verify_cache(dir: &Path) -> Result<Vec<ModelCheck>, ModelError> {
+    let cache_read = |e| ModelError::CacheRead {
+        path: dir.display().to_string(),
+        source: e,
+    };
+    let models = cached_files(dir).map_err(cache_read)?;
+    Ok(models.iter().map(|p| check_model(dir, p)).collect())
+}
+
+/// Pass/fail table for `verify`.
+pub fn verify_report(checks: &[ModelCheck]) -> String {
+    let ok = |b: bool| if b { "ok" } else { "FAIL" };
+    let width = checks.iter().map(|c| c.file_name.len()).max().unwrap_or(0).max(5);
+    let mut out = format!(
+        "{:<width$}  {:>10}  {:<5}  {:<5}  {:<8}  RESULT\n",
+        "MODEL", "SIZE (MB)", "SIZE", "MAGIC", "SHA-256"
+    );
+    for c in checks {
+        let checksum = match c.checksum {
+            ChecksumStatus::Match => "ok",
+            ChecksumStatus::Mismatch => "FAIL",
+            ChecksumStatus::NotRecorded => "-",
+        };
+        out.push_str(&format!(
+            "{:<width$}  {:>10.1}  {:<5}  {:<5}  {:<8}  {}\n",
+            c.file_name,
+            c.size as f64 / 1_000_000.0,
+            ok(c.size_ok),
+            ok(c.magic_ok),
+            checksum,
+            if c.passed() { "PASS" } else { "FAIL" },
+        ));
+    }
+    out
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::ScratchDir;
+
+    #[cfg(not(feature = "embed-model"))]
+    #[test]
+    fn embedded_model_skipped_without_feature() {
+        assert!(EMBEDDED_MODEL.is_none());
+        assert!(extract_embedded_model().unwrap().is_none());
+    }
+
+    #[test]
+    fn explicit_model_overrides_embedded_one() {
+        let name = embedded_model_name("/build/models/ggml-small.bin");
+        assert_eq!(name, Some("small"));
+        assert_eq!(embedded_model_name("/build/model.bin"), None);
+
+        assert!(embedded_serves(name, "medium", false), "default --model");
+        assert!(embedded_serves(name, "small", true));
+        assert!(!embedded_serves(name, "medium", true));
+        assert!(!embedded_serves(None, "medium", true), "unknown size");
+    }
+
+    #[test]
+    fn quantized_variant_filenames_and_urls() {
+        let name = variant_name("medium", "q5_0").unwrap();
+        assert_eq!(name, "medium-q5_0");
+        assert_eq!(model_filename(&name), "ggml-medium-q5_0.bin");
+        assert_eq!(
+            model_url(&name, None),
+            "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium-q5_0.bin"
+        );
+        let mirror = reqwest::Url::parse("https://models.example.com/whisper/").unwrap();
+        assert_eq!(
+            model_url(&name, Some(&mirror)),
+            "https://models.example.com/whisper/ggml-medium-q5_0.bin"
+        );
+        assert_eq!(variant_name("large-v3", "none").unwrap(), "large-v3");
+        assert_eq!(model_filename("large-v3"), "ggml-large-v3.bin");
+        assert_ne!(model_filename("medium"), model_filename(&name));
+    }
+
+    #[test]
+    fn unpublished_quantization_rejected() {
+        let err = variant_name("large-v3", "q8_0").unwrap_err();
+        assert_eq!(err, "no q8_0 variant of the large-v3 model (available: q5_0)");
+        assert!(variant_name("medium", "q5_1").is_err());
+    }
+
+    #[test]
+    fn size_thresholds_per_variant() {
+        assert_eq!(min_model_size("medium"), 1_000_000_000);
+        assert_eq!(min_model_size("medium-q5_0"), 350_000_000);
+        assert_eq!(min_model_size("tiny-q8_0"), 30_000_000);
+        // Every published variant has its own threshold
+        for size in ["tiny", "base", "small", "medium", "large-v3"] {
+            for q in quantizations(size) {
+                let name = variant_name(size, q).unwrap();
+                let min = min_model_size(&name);
+                assert!(min > 0 && min < min_model_size(size), "{name}");
+            }
+        }
+        assert_eq!(context_memory_mb("large-v3-q5_0"), context_memory_mb("large-v3"));
+    }
+
+    #[test]
+    fn timeouts_from_seconds() {
+        let options = DownloadOptions::default();
+        assert_eq!(options.connect_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(options.download_timeout, Some(Duration::from_secs(600)));
+
+        let options = DownloadOptions::from_secs(5, 3600);
+        assert_eq!(options.connect_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(options.download_timeout, Some(Duration::from_secs(3600)));
+
+        // 0 disables the timeout
+        let options = DownloadOptions::from_secs(0, 0);
+        assert_eq!((options.connect_timeout, options.download_timeout), (None, None));
+    }
+
+    #[test]
+    fn timeout_error_names_the_elapsed_limit() {
+        let message =
+            |connecting, options: &DownloadOptions| timeout_error(connecting, options).to_string();
+        let options = DownloadOptions::from_secs(5, 600);
+        assert_eq!(
+            message(true, &options),
+            "Connecting to the model server timed out after 5s"
+        );
+        assert_eq!(message(false, &options), "Download timed out after 600s");
+
+        let options = DownloadOptions::from_secs(0, 0);
+        assert_eq!(message(true, &options), "Download timed out");
+        let options = DownloadOptions::from_secs(0, 20);
+        assert_eq!(message(true, &options), "Download timed out after 20s");
+    }
+
+    #[test]
+    fn available_models_listed_from_bundle_and_cache() {
+        let root = ScratchDir::new("available");
+        let (bundled, cache) = (root.join("model"), root.join("cache"));
+        for dir in [&bundled, &cache.join("medium"), &cache.join("logs")] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        for file in [
+            bundled.join("model.bin"),
+            bundled.join("ggml-small.bin"),
+            cache.join("ggml-tiny.bin"),
+            cache.join("medium/q5_0.bin"),
+            cache.join("medium/q5_0.bin.sha256"),
+            cache.join("medium/f16.part.1"),
+            cache.join("logs/transcriber.log"),
+        ] {
+            std::fs::write(file, b"").unwrap();
+        }
+
+        let models = list_models(&bundled, &cache);
+        let listed: Vec<(Option<&str>, bool)> = models
+            .iter()
+            .map(|m| (m.name.as_deref(), m.bundled))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                (Some("small"), true),
+                (None, true),
+                (Some("tiny"), false),
+                (Some("medium-q5_0"), false),
+            ]
+        );
+
+        let note = available_models_note(&models, &bundled.join("model.bin"));
+        assert!(note.contains("medium-q5_0"));
+        assert!(!note.contains("unknown size"), "failed model not suggested");
+        assert!(available_models_note(&[], &bundled).contains("No other"));
+        assert!(list_models(&root.join("none"), &root.join("none")).is_empty());
+    }
+
+    /// Log sink for checking what reaches the log output.
+    #[derive(Clone, Default)]
+    struct Captured(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn mirror_options(auth: &str) -> DownloadOptions {
+        DownloadOptions {
+            base_url: Some(reqwest::Url::parse("https://models.example.com/whisper").unwrap()),
+            auth: Some(auth.parse().unwrap()),
+            ..DownloadOptions::default()
+        }
+    }
+
+    #[test]
+    fn auth_header_sent_only_to_model_url_host() {
+        let options = mirror_options("Authorization: Bearer s3cr3t-t0ken");
+        let url = model_url("medium", options.base_url.as_ref());
+        let auth = auth_for(&url, &options).expect("sent to the configured host");
+        assert_eq!(auth.name, "authorization");
+        assert_eq!(auth.value, "Bearer s3cr3t-t0ken");
+
+        // Never to any other host, nor to HuggingFace without --model-url
+        assert!(auth_for(&model_url("medium", None), &options).is_none());
+        assert!(auth_for("https://models.example.com.evil.test/x", &options).is_none());
+        let public = DownloadOptions {
+            base_url: None,
+            ..options.clone()
+        };
+        assert!(auth_for(&model_url("medium", None), &public).is_none());
+        assert!(auth_for(&url, &DownloadOptions::default()).is_none());
+
+        assert!("Authorization".parse::<AuthHeader>().is_err());
+        assert!("Bad Name: x".parse::<AuthHeader>().is_err());
+        let err = "X-Token: \u{7f}s3cr3t".parse::<AuthHeader>().unwrap_err();
+        assert!(!err.contains("s3cr3t"), "parse errors don't echo the value");
+    }
+
+    #[test]
+    fn auth_header_value_redacted_in_logs() {
+        let options = mirror_options("Authorization: Bearer s3cr3t-t0ken");
+        let captured = Captured::default();
+        let sink = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || sink.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            auth_for(&model_url("medium", options.base_url.as_ref()), &options);
+            auth_for(&model_url("medium", None), &options);
+            debug!(?options, "Download options");
+        });
+
+        let logs = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("authorization: <redacted>"), "{logs}");
+        assert!(!logs.contains("s3cr3t"), "{logs}");
+    }
+
+    #[test]
+    fn nested_layout_paths() {
+        let cache = Path::new("cache");
+        assert_eq!(nested_path(cache, "medium"), cache.join("medium/f16.bin"));
+        assert_eq!(
+            nested_path(cache, "large-v3-q5_0"),
+            cache.join("large-v3/q5_0.bin")
+        );
+        for model in ["medium", "large-v3", "small-q8_0"] {
+            let name = cached_model_name(&nested_path(cache, model));
+            assert_eq!(name.as_deref(), Some(model));
+            let flat = cached_model_name(&cache.join(model_filename(model)));
+            assert_eq!(flat.as_deref(), Some(model));
+        }
+    }
+
+    #[test]
+    fn cached_model_found_in_either_layout() {
+        let cache = ScratchDir::new("resolve");
+        assert_eq!(find_cached(&cache, "tiny"), None);
+
+        // Legacy flat layout
+        std::fs::write(cache.join("ggml-tiny.bin"), b"flat").unwrap();
+        assert_eq!(
+            find_cached(&cache, "tiny"),
+            Some(cache.join("ggml-tiny.bin"))
+        );
+
+        // Nested layout wins when both exist
+        std::fs::create_dir_all(cache.join("tiny")).unwrap();
+        std::fs::write(cache.join("tiny/f16.bin"), b"nested").unwrap();
+        assert_eq!(
+            find_cached(&cache, "tiny"),
+            Some(cache.join("tiny/f16.bin"))
+        );
+        assert_eq!(find_cached(&cache, "tiny-q5_1"), None);
+    }
+
+    #[test]
+    fn flat_cache_migrated_to_nested_layout() {
+        let cache = ScratchDir::new("migrate");
+        std::fs::write(cache.join("ggml-base.bin"), b"base").unwrap();
+        std::fs::write(
+            cache.join("ggml-base.bin.sha256"),
+            "abc123  ggml-base.bin\n",
+        )
+        .unwrap();
+        std::fs::write(cache.join("ggml-medium-q5_0.bin"), b"medium").unwrap();
+        std::fs::write(cache.join("ggml-custom-finetune.bin"), b"own").unwrap();
+        std::fs::write(cache.join("ggml-medium-q4_0.bin"), b"own").unwrap();
+        std::fs::create_dir_all(cache.join("logs")).unwrap();
+
+        assert_eq!(migrate_flat_cache(&cache), 2);
+        assert_eq!(std::fs::read(cache.join("base/f16.bin")).unwrap(), b"base");
+        assert_eq!(
+            std::fs::read(cache.join("medium/q5_0.bin")).unwrap(),
+            b"medium"
+        );
+        assert_eq!(
+            std::fs::read_to_string(cache.join("base/f16.bin.sha256")).unwrap(),
+            "abc123  f16.bin\n"
+        );
+        assert!(!cache.join("ggml-base.bin").exists());
+        assert!(!cache.join("ggml-base.bin.sha256").exists());
+        // Not a published model: left alone
+        assert!(cache.join("ggml-custom-finetune.bin").exists());
+        assert!(cache.join("ggml-medium-q4_0.bin").exists());
+
+        // One-time: nothing left to move
+        assert_eq!(migrate_flat_cache(&cache), 0);
+    }
+
+    #[test]
+    fn cache_migrated_only_on_first_resolve() {
+        let cache = ScratchDir::new("migrate-once");
+        std::fs::write(cache.join("ggml-tiny.bin"), b"tiny").unwrap();
+        migrate_flat_cache_once(&cache);
+        assert!(cache.join("tiny/f16.bin").exists());
+        assert!(cache.join(MIGRATED_MARKER).exists());
+
+        std::fs::write(cache.join("ggml-base.bin"), b"base").unwrap();
+        migrate_flat_cache_once(&cache);
+        assert!(
+            cache.join("ggml-base.bin").exists(),
+            "no rescan once marked"
+        );
+        assert_eq!(
+            find_cached(&cache, "base"),
+            Some(cache.join("ggml-base.bin"))
+        );
+    }
+
+    #[test]
+    fn known_models_recognised() {
+        for model in ["tiny", "large-v3", "medium-q5_0", "small-q8_0"] {
+            assert!(is_known_model(model), "{model}");
+        }
+        for model in ["huge", "medium-q4_0", "large-v3-q8_0", "custom-finetune"] {
+            assert!(!is_known_model(model), "{model}");
+        }
+    }
+
+    #[test]
+    fn failed_attempt_partial_removed_or_kept() {
+        let dir = ScratchDir::new("partial");
+        let dest = dir.join("ggml-tiny.bin");
+        let part = dir.join("ggml-tiny.part");
+
+        std::fs::write(&part, b"truncated").unwrap();
+        assert_eq!(discard_partial(&dest, 1, false), None);
+        assert!(!part.exists(), "cleaned up by default");
+
+        std::fs::write(&part, b"truncated").unwrap();
+        let kept = discard_partial(&dest, 2, true).unwrap();
+        assert_eq!(kept, dir.join("ggml-tiny.part.2"));
+        assert!(!part.exists());
+        assert_eq!(std::fs::read(&kept).unwrap(), b"truncated");
+
+        assert_eq!(discard_partial(&dest, 3, true), None, "nothing to keep");
+    }
+
+    #[test]
+    fn verify_flags_corrupted_model() {
+        let dir = ScratchDir::new("verify");
+
+        let good = dir.join("ggml-custom.bin");
+        std::fs::write(&good, [&GGML_MAGIC[..], &[7u8; 64]].concat()).unwrap();
+        let hash = sha256_hex(&good).unwrap();
+        std::fs::write(checksum_path(&good), format!("{hash}  ggml-custom.bin\n")).unwrap();
+
+        // Written with a valid checksum, then overwritten with garbage
+        let bad = dir.join("ggml-broken.bin");
+        std::fs::write(&bad, [&GGML_MAGIC[..], &[7u8; 64]].concat()).unwrap();
+        std::fs::write(checksum_path(&bad), format!("{hash}  ggml-broken.bin\n")).unwrap();
+        std::fs::write(&bad, [0u8; 68]).unwrap();
+
+        let checks = verify_cache(&dir).unwrap();
+
+        assert_eq!(checks.len(), 2, "sidecars are not models");
+        let (broken, custom) = (&checks[0], &checks[1]);
+        assert_eq!(custom.file_name, "ggml-custom.bin");
+        assert!(custom.passed());
+        assert_eq!(custom.checksum, ChecksumStatus::Match);
+
+        assert_eq!(broken.file_name, "ggml-broken.bin");
+        assert!(!broken.passed());
+        assert!(!broken.magic_ok);
+        assert_eq!(broken.checksum, ChecksumStatus::Mismatch);
+
+        let report = verify_report(&checks);
+        assert_eq!(report.lines().count(), 3);
+        assert!(report.lines().nth(1).unwrap().ends_with("FAIL"));
+        assert!(report.lines().nth(2).unwrap().ends_with("PASS"));
+    }
+
+    #[test]
+    fn undersized_model_fails_threshold() {
+        let dir = ScratchDir::new("verify-small");
+        let path = dir.join("ggml-medium.bin");
+        std::fs::write(&path, GGML_MAGIC).unwrap();
+        let check = check_model(&dir, &path);
+        assert!(!check.size_ok && check.magic_ok);
+        assert_eq!(check.checksum, ChecksumStatus::NotRecorded);
+        assert!(!check.passed());
+    }
+}
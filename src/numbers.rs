@@ -0,0 +1,389 @@
+//! Turkish number normalization between digits and words.
+//!
+//! Converts standalone numbers in either direction: `2024` ↔ `iki bin
+//! yirmi dört`. Only whole space-separated tokens are touched, so suffixed
+//! forms (`2024'te`, `ikinci`) and decimals (`3.5`) are left alone.
+
+use crate::postprocess::turkish_lowercase;
+
+/// Target form for numbers in the transcript.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumberStyle {
+    /// `yirmi dört` → `24`
+    Digits,
+    /// `24` → `yirmi dört`
+    Words,
+}
+
+/// Largest value handled in either direction (999 milyar …).
+const MAX_VALUE: u64 = 999_999_999_999;
+
+static UNITS: &[&str] = &[
+    "sıfır", "bir", "iki", "üç", "dört", "beş", "altı", "yedi", "sekiz", "dokuz",
+];
+
+static TENS: &[&str] = &[
+    "", "on", "yirmi", "otuz", "kırk", "elli", "altmış", "yetmiş", "seksen", "doksan",
+];
+
+/// Scale words, largest first.
+static SCALES: &[(u64, &str)] = &[
+    (1_000_000_000, "milyar"),
+    (1_000_000, "milyon"),
+    (1_000, "bin"),
+];
+
+/// Number words that are too ambiguous to convert on their own: `bir` is
+/// also the indefinite article, `yüz` means "face", `bin` means "ride",
+/// `yedi` means "ate" and `altı` means "its bottom".
+static AMBIGUOUS_ALONE: &[&str] = &["bir", "yüz", "bin", "yedi", "altı"];
+
+/// Convert numbers in `text` to the requested style.
+pub fn normalize(text: &str, style: NumberStyle) -> String {
+    match style {
+        NumberStyle::Digits => words_to_digits(text),
+        NumberStyle::Words => digits_to_words(text),
+    }
+}
+
+// ── Digits → Words ──────────────────────────────────────────────────
+
+fn digits_to_words(text: &str) -> String {
+    text.split(' ')
+        .map(|token| {
+            let (prefix, core, suffix) = split_punctuation(token);
+            match parse_digits(core) {
+                Some(n) => format!("{prefix}{}{suffix}", to_words(n)),
+                None => token.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse a plain digit run with no leading zeros.
+fn parse_digits(core: &str) -> Option<u64> {
+    if core.is_empty() || !core.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if core.len() > 1 && core.starts_with('0') {
+        return None;
+    }
+    core.parse().ok().filter(|&n| n <= MAX_VALUE)
+}
+
+/// Spell out `n` in Turkish (`1200000` → `bir milyon iki yüz bin`).
+pub fn to_words(mut n: u64) -> String {
+    if n == 0 {
+        return UNITS[0].to_string();
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    for &(scale, name) in SCALES {
+        let count = n / scale;
+        if count == 0 {
+            continue;
+        }
+        // "bin", never "bir bin"; but "bir milyon", "bir milyar"
+        if !(scale == 1_000 && count == 1) {
+            parts.push(below_thousand(count));
+        }
+        parts.push(name.to_string());
+        n %= scale;
+    }
+    if n > 0 {
+        parts.push(below_thousand(n));
+    }
+
+    parts.join(" ")
+}
+
+fn below_thousand(n: u64) -> String {
+    let (hundreds, tens, units) = (
+        (n / 100) as usize,
+        (n / 10 % 10) as usize,
+        (n % 10) as usize,
+    );
+    let mut parts: Vec<&str> = Vec::new();
+    if hundreds > 1 {
+        parts.push(UNITS[hundreds]);
+    }
+    if hundreds > 0 {
+        parts.push("yüz");
+    }
+    if tens > 0 {
+        parts.push(TENS[tens]);
+    }
+    if units > 0 {
+        parts.push(UNITS[units]);
+    }
+    parts.join(" ")
+}
+
+// ── Words → Digits ──────────────────────────────────────────────────
+
+fn words_to_digits(text: &str) -> String {
+    let tokens: Vec<&str> = text.split(' ').collect();
+    let mut out: Vec<String> = Vec::with_capacity(tokens.len());
+
+    let mut i = 0;
+    while i < tokens.len() {
+        match longest_number(&tokens[i..]) {
+            Some((len, value)) => {
+                let (prefix, _, _) = split_punctuation(tokens[i]);
+                let (_, _, suffix) = split_punctuation(tokens[i + len - 1]);
+                out.push(format!("{prefix}{value}{suffix}"));
+                i += len;
+            }
+            None => {
+                out.push(tokens[i].to_string());
+                i += 1;
+            }
+        }
+    }
+
+    out.join(" ")
+}
+
+/// Longest run of number words at the start of `tokens` that forms a
+/// valid number. Returns `(token_count, value)`.
+fn longest_number(tokens: &[&str]) -> Option<(usize, u64)> {
+    let mut words: Vec<String> = Vec::new();
+    let mut best = None;
+
+    for (i, token) in tokens.iter().enumerate() {
+        let (prefix, core, suffix) = split_punctuation(token);
+        // Punctuation may only open the first word or close the last one
+        if i > 0 && !prefix.is_empty() {
+            break;
+        }
+        words.push(turkish_lowercase(core));
+        // Grammar errors can't be repaired by more words, so stop at the first
+        let value = match parse_words(&words) {
+            Some(v) => v,
+            None => break,
+        };
+        let lone_ambiguous = words.len() == 1 && AMBIGUOUS_ALONE.contains(&words[0].as_str());
+        if !lone_ambiguous {
+            best = Some((i + 1, value));
+        }
+        if !suffix.is_empty() {
+            break;
+        }
+    }
+
+    best
+}
+
+/// Parse a full sequence of Turkish number words, enforcing the grammar
+/// (`iki yüz`, not `yüz iki yüz`; `bin`, not `bir bin`). Returns `None`
+/// for anything that isn't a single well-formed number.
+fn parse_words(words: &[String]) -> Option<u64> {
+    if words.len() == 1 && words[0] == UNITS[0] {
+        return Some(0);
+    }
+
+    let mut total: u64 = 0;
+    // Value of the group below the current scale (0..=999)
+    let mut group: u64 = 0;
+    // Smallest scale used so far; later scales must be smaller
+    let mut last_scale = u64::MAX;
+    // Explicit "bir" with no other digits in the group
+    let mut group_is_bare_one = false;
+
+    for word in words {
+        let w = word.as_str();
+        if let Some(u) = UNITS.iter().skip(1).position(|&x| x == w) {
+            if !group.is_multiple_of(10) {
+                return None;
+            }
+            group += u as u64 + 1;
+            group_is_bare_one = group == 1;
+        } else if let Some(t) = TENS.iter().skip(1).position(|&x| x == w) {
+            if !group.is_multiple_of(100) {
+                return None;
+            }
+            group += (t as u64 + 1) * 10;
+            group_is_bare_one = false;
+        } else if w == "yüz" {
+            if group >= 10 || group_is_bare_one {
+                return None;
+            }
+            group = group.max(1) * 100;
+            group_is_bare_one = false;
+        } else if let Some(&(scale, _)) = SCALES.iter().find(|(_, name)| *name == w) {
+            if scale >= last_scale {
+                return None;
+            }
+            let count = if scale == 1_000 {
+                if group_is_bare_one {
+                    return None;
+                }
+                group.max(1)
+            } else if group == 0 {
+                return None;
+            } else {
+                group
+            };
+            total += count * scale;
+            last_scale = scale;
+            group = 0;
+            group_is_bare_one = false;
+        } else {
+            return None;
+        }
+    }
+
+    Some(total + group).filter(|&n| n <= MAX_VALUE)
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────
+
+/// Split a token into leading punctuation, alphanumeric core, and trailing
+/// punctuation. Returns an empty core if punctuation appears inside it.
+pub(crate) fn split_punctuation(token: &str) -> (&str, &str, &str) {
+    let start = token
+        .find(|c: char| c.is_alphanumeric())
+        .unwrap_or(token.len());
+    let end = token
+        .rfind(|c: char| c.is_alphanumeric())
+        .map(|i| i + token[i..].chars().next().map_or(1, char::len_utf8))
+        .unwrap_or(start);
+    let core = &token[start..end.max(start)];
+    if core.chars().any(|c| !c.is_alphanumeric()) {
+        return (token, "", "");
+    }
+    (&token[..start], core, &token[end.max(start)..])
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        text.split(' ').map(str::to_string).collect()
+    }
+
+    const CASES: &[(u64, &str)] = &[
+        (0, "sıfır"),
+        (7, "yedi"),
+        (10, "on"),
+        (15, "on beş"),
+        (24, "yirmi dört"),
+        (90, "doksan"),
+        (100, "yüz"),
+        (101, "yüz bir"),
+        (200, "iki yüz"),
+        (999, "dokuz yüz doksan dokuz"),
+        (1_000, "bin"),
+        (1_001, "bin bir"),
+        (1_984, "bin dokuz yüz seksen dört"),
+        (2_024, "iki bin yirmi dört"),
+        (10_000, "on bin"),
+        (100_000, "yüz bin"),
+        (101_000, "yüz bir bin"),
+        (1_000_000, "bir milyon"),
+        (1_200_000, "bir milyon iki yüz bin"),
+        (2_000_000_000, "iki milyar"),
+        (
+            123_456_789,
+            "yüz yirmi üç milyon dört yüz elli altı bin yedi yüz seksen dokuz",
+        ),
+    ];
+
+    #[test]
+    fn digits_spelled_out() {
+        for &(n, text) in CASES {
+            assert_eq!(to_words(n), text, "to_words({n})");
+        }
+    }
+
+    #[test]
+    fn words_parsed_to_digits() {
+        for &(n, text) in CASES {
+            assert_eq!(parse_words(&words(text)), Some(n), "parse_words({text:?})");
+        }
+    }
+
+    #[test]
+    fn round_trip_through_text() {
+        // Lone "yedi"/"yüz"/"bin" are deliberately left as words
+        for &(n, _) in CASES.iter().filter(|(n, _)| ![7, 100, 1_000].contains(n)) {
+            let digits = n.to_string();
+            let spelled = normalize(&digits, NumberStyle::Words);
+            assert_eq!(normalize(&spelled, NumberStyle::Digits), digits);
+        }
+    }
+
+    #[test]
+    fn ungrammatical_sequences_rejected() {
+        assert_eq!(parse_words(&words("bir bin")), None);
+        assert_eq!(parse_words(&words("bir yüz")), None);
+        assert_eq!(parse_words(&words("dört yirmi")), None);
+        assert_eq!(parse_words(&words("bin milyon")), None);
+        assert_eq!(parse_words(&words("milyon")), None);
+        assert_eq!(parse_words(&words("iki üç")), None);
+    }
+
+    #[test]
+    fn words_to_digits_in_sentence() {
+        assert_eq!(
+            normalize("Yirmi dört kişi geldi.", NumberStyle::Digits),
+            "24 kişi geldi."
+        );
+        assert_eq!(
+            normalize("Bütçe bir milyon iki yüz bin lira.", NumberStyle::Digits),
+            "Bütçe 1200000 lira."
+        );
+        assert_eq!(
+            normalize("İki bin yirmi dört yılında", NumberStyle::Digits),
+            "2024 yılında"
+        );
+    }
+
+    #[test]
+    fn separate_numbers_not_merged() {
+        // "iki üç" is not one number — convert each on its own
+        assert_eq!(normalize("iki üç elma", NumberStyle::Digits), "2 3 elma");
+        assert_eq!(normalize("on, yirmi", NumberStyle::Digits), "10, 20");
+    }
+
+    #[test]
+    fn lone_ambiguous_words_untouched() {
+        assert_eq!(
+            normalize("bir gün geldi", NumberStyle::Digits),
+            "bir gün geldi"
+        );
+        assert_eq!(normalize("yüz yüze", NumberStyle::Digits), "yüz yüze");
+        assert_eq!(normalize("yemek yedi", NumberStyle::Digits), "yemek yedi");
+        assert_eq!(
+            normalize("masanın altı", NumberStyle::Digits),
+            "masanın altı"
+        );
+        // but fine as part of a longer number
+        assert_eq!(normalize("bir milyon", NumberStyle::Digits), "1000000");
+        assert_eq!(normalize("yedi yüz", NumberStyle::Digits), "700");
+        assert_eq!(normalize("on altı", NumberStyle::Digits), "16");
+    }
+
+    #[test]
+    fn digits_to_words_in_sentence() {
+        assert_eq!(
+            normalize("2024 yılında 3 toplantı yaptık.", NumberStyle::Words),
+            "iki bin yirmi dört yılında üç toplantı yaptık."
+        );
+        assert_eq!(
+            normalize("Sonuç: 15.", NumberStyle::Words),
+            "Sonuç: on beş."
+        );
+    }
+
+    #[test]
+    fn suffixed_and_decimal_numbers_untouched() {
+        let text = "2024'te oran 3.5 idi, kod 007";
+        assert_eq!(normalize(text, NumberStyle::Words), text);
+        assert_eq!(normalize("ikinci gün", NumberStyle::Digits), "ikinci gün");
+    }
+}
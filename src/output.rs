@@ -0,0 +1,963 @@
+//! Transcript writers: plain text, JSON, and dispatch to the subtitle
+//! renderers for each requested output format.
+
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::errors::OutputError;
+use crate::header::{self, HeaderFields, HeaderTemplate};
+use crate::review;
+use crate::sentences;
+use crate::subtitle;
+use crate::transcribe::{Segment, DEFAULT_LANGUAGE};
+
+/// What to do when the output file already exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriteMode {
+    /// Refuse to touch an existing file (default)
+    Protect,
+    /// Truncate and replace an existing file (`--overwrite`)
+    Overwrite,
+    /// Add to the end of an existing file (`--append`)
+    Append,
+}
+
+/// Byte encoding of written output files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// Plain UTF-8 (default)
+    #[default]
+    Utf8,
+    /// UTF-8 with a leading byte-order mark, for editors that otherwise
+    /// assume a legacy code page (`--output-encoding utf8-bom`)
+    Utf8Bom,
+}
+
+impl Encoding {
+    /// The encoding used for a file in `format`: the byte-order mark is
+    /// only for text formats that editors open; JSON parsers reject it.
+    pub fn for_format(self, format: &str) -> Self {
+        match format {
+            "txt" | "plain" | "lines" | "review" | "srt" | "vtt" => self,
+            _ => Self::Utf8,
+        }
+    }
+}
+
+/// The UTF-8 byte-order mark.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Layout options for the plain-text transcript.
+#[derive(Clone)]
+pub struct TextOptions {
+    /// Write the `=== TRANSCRIPT ===` header block
+    pub header: bool,
+    /// Custom header replacing the default block (`--header-template`)
+    pub header_template: Option<HeaderTemplate>,
+    /// Write the `=== TIMESTAMPED ===` section
+    pub timestamps: bool,
+    /// Insert a `[pause: Ns]` marker where the gap between segments
+    /// exceeds this many seconds
+    pub mark_pauses: Option<f64>,
+    /// Annotate likely speaker turns (silence-gap heuristic)
+    pub speakers: Option<SpeakerMarks>,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            header: true,
+            header_template: None,
+            timestamps: true,
+            mark_pauses: None,
+            speakers: None,
+        }
+    }
+}
+
+/// Speaker-turn annotation settings.
+///
+/// This is a heuristic, not acoustic diarization: a silence longer than
+/// `gap` seconds is assumed to be a change of speaker, alternating between
+/// two speakers.
+#[derive(Clone)]
+pub struct SpeakerMarks {
+    pub style: SpeakerStyle,
+    /// Minimum silence (seconds) treated as a speaker turn
+    pub gap: f64,
+}
+
+/// How speaker turns are shown in the transcript.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpeakerStyle {
+    /// A `--- Speaker change ---` line at each turn
+    Change,
+    /// Alternating `Speaker 1:` / `Speaker 2:` labels
+    Labels,
+}
+
+const SPEAKER_CHANGE_MARKER: &str = "--- Speaker change ---";
+
+impl TextOptions {
+    /// Bare joined text only: no header, no timestamped section.
+    pub fn plain(mut self) -> Self {
+        self.header = false;
+        self.timestamps = false;
+        self
+    }
+}
+
+// ── Output targets ──────────────────────────────────────────────────
+
+/// One output file to write from a single transcription.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    pub format: String,
+    pub path: PathBuf,
+}
+
+/// File extension written for `format` (`plain` is still a `.txt`).
+pub fn extension(format: &str) -> &str {
+    match format {
+        "plain" => "txt",
+        "lines" => "lines.txt",
+        "review" => "review.txt",
+        _ => format,
+    }
+}
+
+/// Reject format lists that would write two outputs to the same file
+/// (e.g. `txt,plain`) or repeat a format.
+pub fn check_formats(formats: &[String]) -> Result<(), String> {
+    for (i, format) in formats.iter().enumerate() {
+        if let Some(other) = formats[..i]
+            .iter()
+            .find(|f| extension(f) == extension(format))
+        {
+            return Err(format!(
+                "formats `{other}` and `{format}` would both write a .{} file",
+                extension(format)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Output path that means "write to stdout".
+pub const STDOUT_PATH: &str = "-";
+
+fn is_stdout(path: &Path) -> bool {
+    path == Path::new(STDOUT_PATH)
+}
+
+/// Output targets for an explicit `--output` path. With a single format
+/// the path is used as given; with several, each format replaces the
+/// path's extension.
+pub fn targets_at(path: &Path, formats: &[String]) -> Vec<Target> {
+    match formats {
+        [format] => vec![Target {
+            format: format.clone(),
+            path: path.to_path_buf(),
+        }],
+        _ => formats
+            .iter()
+            .map(|format| Target {
+                format: format.clone(),
+                path: path.with_extension(extension(format)),
+            })
+            .collect(),
+    }
+}
+
+/// Transcript and metadata handed to every writer.
+pub struct Document<'a> {
+    /// Source path as it should appear in the output (see [`source_path`])
+    pub source: &'a Path,
+    pub model_size: &'a str,
+    /// Inference time in seconds, shown in the text header
+    pub duration: f64,
+    pub segments: &'a [Segment],
+}
+
+/// How the source audio path is shown in transcript headers and JSON.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathStyle {
+    /// File name only (default)
+    Name,
+    /// Path relative to the input root (batch directory or working directory)
+    Relative,
+    /// Full canonical path
+    Absolute,
+}
+
+/// The source path to record for `source` in `style`. A relative path is
+/// taken against `base`; a source outside `base` falls back to its
+/// absolute path.
+pub fn source_path(source: &Path, style: PathStyle, base: &Path) -> PathBuf {
+    match style {
+        PathStyle::Name => source
+            .file_name()
+            .map_or_else(|| source.to_path_buf(), PathBuf::from),
+        PathStyle::Absolute => absolute(source),
+        PathStyle::Relative => {
+            let source = absolute(source);
+            match source.strip_prefix(absolute(base)) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => source,
+            }
+        }
+    }
+}
+
+/// Canonical form of `path` (as given if it doesn't exist), without the
+/// `\\?\` prefix Windows adds to canonical paths.
+fn absolute(path: &Path) -> PathBuf {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    match path.to_str().and_then(|p| p.strip_prefix(r"\\?\")) {
+        Some(stripped) => PathBuf::from(stripped),
+        None => path,
+    }
+}
+
+/// Render `doc` in `format`.
+pub fn render(
+    format: &str,
+    doc: &Document,
+    text: &TextOptions,
+    max_line_length: usize,
+) -> Result<String, OutputError> {
+    let Document {
+        source,
+        model_size,
+        duration,
+        segments,
+    } = *doc;
+    Ok(match format {
+        "srt" => subtitle::render_srt(segments, max_line_length),
+        "vtt" => subtitle::render_vtt(segments, max_line_length),
+        "json" => render_json(doc)?,
+        "lines" => render_lines(segments),
+        "review" => review::render_review(doc),
+        "plain" => render_text(
+            source,
+            model_size,
+            duration,
+            segments,
+            &text.clone().plain(),
+        ),
+        _ => render_text(source, model_size, duration, segments, text),
+    })
+}
+
+/// Render `doc` once per target and write each file according to `mode`.
+/// Standard output and JSON never get a byte-order mark.
+pub fn write_outputs(
+    targets: &[Target],
+    doc: &Document,
+    text: &TextOptions,
+    max_line_length: usize,
+    mode: WriteMode,
+    encoding: Encoding,
+) -> Result<(), OutputError> {
+    for target in targets {
+        let contents = render(&target.format, doc, text, max_line_length)?;
+        if is_stdout(&target.path) {
+            std::io::stdout()
+                .lock()
+                .write_all(contents.as_bytes())
+                .map_err(|e| OutputError::WriteFailed(e.to_string()))?;
+        } else {
+            let encoding = encoding.for_format(&target.format);
+            write_file(&target.path, &contents, mode, encoding)?;
+        }
+    }
+    Ok(())
+}
+
+/// One sentence per line, no header: the segment texts joined and split
+/// at Turkish sentence boundaries.
+pub fn render_lines(segments: &[Segment]) -> String {
+    let text = segments
+        .iter()
+        .map(|s| s.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    sentences::split_sentences(&text)
+        .into_iter()
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Render the transcript as JSON: source metadata plus the segment list.
+/// Per-token probabilities stay out of it.
+pub fn render_json(doc: &Document) -> Result<String, OutputError> {
+    #[derive(Serialize)]
+    struct Json<'a> {
+        source: String,
+        model: String,
+        duration_secs: f64,
+        segments: Vec<JsonSegment<'a>>,
+    }
+
+    #[derive(Serialize)]
+    struct JsonSegment<'a> {
+        start: f64,
+        end: f64,
+        text: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        confidence: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        language: Option<&'a str>,
+    }
+
+    let json = Json {
+        source: doc.source.display().to_string(),
+        model: format!("whisper-{}", doc.model_size),
+        duration_secs: doc.duration,
+        segments: doc
+            .segments
+            .iter()
+            .map(|seg| JsonSegment {
+                start: seg.start,
+                end: seg.end,
+                text: &seg.text,
+                confidence: seg.confidence,
+                language: seg.language.as_deref(),
+            })
+            .collect(),
+    };
+    let mut out =
+        serde_json::to_string_pretty(&json).map_err(|e| OutputError::WriteFailed(e.to_string()))?;
+    out.push('\n');
+    Ok(out)
+}
+
+// ── Plain text ──────────────────────────────────────────────────────
+
+/// Render the transcript matching the Python version's format exactly.
+pub fn render_text(
+    source: &Path,
+    model_size: &str,
+    duration: f64,
+    segments: &[Segment],
+    opts: &TextOptions,
+) -> String {
+    let mut out = String::new();
+
+    if segments.is_empty() {
+        if opts.header {
+            out.push_str("No speech detected in the audio.\n");
+        }
+        return out;
+    }
+
+    // Header
+    if let (true, Some(template)) = (opts.header, &opts.header_template) {
+        let header = template.render(&HeaderFields {
+            source: &source.display().to_string(),
+            model: model_size,
+            duration,
+            date: &header::today(),
+            language: &languages(segments),
+        });
+        out.push_str(header.trim_end_matches('\n'));
+        out.push_str("\n\n");
+    } else if opts.header {
+        out.push_str("=== TRANSCRIPT (Turkish) ===\n");
+        let _ = writeln!(out, "Source: {}", source.display());
+        let _ = writeln!(out, "Model: whisper-{model_size}");
+        let _ = writeln!(out, "Duration: {duration:.1}s");
+        let _ = writeln!(out, "{}", "=".repeat(40));
+        out.push('\n');
+    }
+
+    let speakers = opts
+        .speakers
+        .as_ref()
+        .map(|m| (m.style, speaker_turns(segments, m.gap)));
+    let turn_at = |i: usize| match &speakers {
+        Some((_, turns)) => i > 0 && turns[i] != turns[i - 1],
+        None => false,
+    };
+    let label = |i: usize| match &speakers {
+        Some((SpeakerStyle::Labels, turns)) => format!("Speaker {}: ", turns[i] + 1),
+        _ => String::new(),
+    };
+    let change_marker = matches!(speakers, Some((SpeakerStyle::Change, _)));
+
+    // Full text — long pauses and speaker turns start a new paragraph
+    for (i, seg) in segments.iter().enumerate() {
+        let turn = turn_at(i);
+        if i > 0 {
+            let sep = match pause_before(segments, i, opts.mark_pauses) {
+                Some(_) => "\n\n",
+                None if turn => "\n\n",
+                None => " ",
+            };
+            out.push_str(sep);
+        }
+        if turn && change_marker {
+            let _ = writeln!(out, "{SPEAKER_CHANGE_MARKER}");
+        }
+        if i == 0 || turn {
+            out.push_str(&label(i));
+        }
+        out.push_str(&seg.text);
+    }
+    out.push('\n');
+
+    if !opts.timestamps {
+        return out;
+    }
+
+    // Timestamped segments
+    out.push('\n');
+    out.push_str("=== TIMESTAMPED ===\n\n");
+    for (i, seg) in segments.iter().enumerate() {
+        if let Some(gap) = pause_before(segments, i, opts.mark_pauses) {
+            let _ = writeln!(out, "[pause: {}s]", gap.round() as u64);
+        }
+        if turn_at(i) && change_marker {
+            let _ = writeln!(out, "{SPEAKER_CHANGE_MARKER}");
+        }
+        let (sm, ss) = (seg.start as u64 / 60, seg.start as u64 % 60);
+        let (em, es) = (seg.end as u64 / 60, seg.end as u64 % 60);
+        let _ = writeln!(
+            out,
+            "[{sm:02}:{ss:02} -> {em:02}:{es:02}]  {}{}",
+            label(i),
+            seg.text
+        );
+    }
+
+    out
+}
+
+/// Languages of `segments` in order of first appearance, comma-separated
+/// (`tr` unless `--multilingual` tagged them).
+fn languages(segments: &[Segment]) -> String {
+    let mut seen: Vec<&str> = Vec::new();
+    for seg in segments {
+        let language = seg.language.as_deref().unwrap_or(DEFAULT_LANGUAGE);
+        if !seen.contains(&language) {
+            seen.push(language);
+        }
+    }
+    if seen.is_empty() {
+        seen.push(DEFAULT_LANGUAGE);
+    }
+    seen.join(", ")
+}
+
+/// Fail early if `path` exists and `mode` protects it, so a long
+/// transcription isn't wasted on an output that can't be written.
+pub fn check_target(path: &Path, mode: WriteMode) -> Result<(), OutputError> {
+    if mode == WriteMode::Protect && !is_stdout(path) && path.exists() {
+        return Err(OutputError::AlreadyExists {
+            path: path.display().to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Make sure the directory `path` will be written into exists and accepts
+/// new files: create it if missing, then probe with a temporary file.
+pub fn check_writable_dir(path: &Path) -> Result<(), OutputError> {
+    if is_stdout(path) {
+        return Ok(());
+    }
+    let dir = match path.parent() {
+        Some(d) if !d.as_os_str().is_empty() => d,
+        _ => Path::new("."),
+    };
+    let not_writable = |e| OutputError::DirNotWritable {
+        path: dir.display().to_string(),
+        source: e,
+    };
+
+    std::fs::create_dir_all(dir).map_err(not_writable)?;
+    // Unique per call: parallel `--jobs` workers probe the same directory
+    static NEXT_PROBE: AtomicUsize = AtomicUsize::new(0);
+    let probe = dir.join(format!(
+        ".transcriber-write-test-{}-{}",
+        std::process::id(),
+        NEXT_PROBE.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(not_writable)?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Write a rendered document according to `mode`. With
+/// `Encoding::Utf8Bom` the mark is written only when the file starts out
+/// empty, so appending never puts a second one mid-file.
+#[tracing::instrument(skip_all, fields(path = %path.display(), mode = ?mode))]
+pub fn write_file(
+    path: &Path,
+    contents: &str,
+    mode: WriteMode,
+    encoding: Encoding,
+) -> Result<(), OutputError> {
+    let mut options = std::fs::OpenOptions::new();
+    match mode {
+        WriteMode::Protect => options.write(true).create_new(true),
+        WriteMode::Overwrite => options.write(true).create(true).truncate(true),
+        WriteMode::Append => options.append(true).create(true),
+    };
+
+    let mut f = options.open(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::AlreadyExists {
+            OutputError::AlreadyExists {
+                path: path.display().to_string(),
+            }
+        } else {
+            OutputError::FileCreate {
+                path: path.display().to_string(),
+                source: e,
+            }
+        }
+    })?;
+    let write_failed = |e: std::io::Error| OutputError::WriteFailed(e.to_string());
+
+    let empty = f.metadata().map_err(write_failed)?.len() == 0;
+    if encoding == Encoding::Utf8Bom && empty {
+        f.write_all(UTF8_BOM).map_err(write_failed)?;
+    }
+    f.write_all(contents.as_bytes()).map_err(write_failed)
+}
+
+/// Write `<path>.sha256` next to `path` in the `<hash>  <filename>`
+/// format read by `sha256sum -c`. Returns the sidecar path.
+pub fn write_checksum(path: &Path) -> Result<PathBuf, OutputError> {
+    let contents = std::fs::read(path).map_err(|e| OutputError::WriteFailed(e.to_string()))?;
+    let hash: String = Sha256::digest(&contents)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let sidecar = path.with_file_name(format!("{file_name}.sha256"));
+    std::fs::write(&sidecar, format!("{hash}  {file_name}\n")).map_err(|e| {
+        OutputError::FileCreate {
+            path: sidecar.display().to_string(),
+            source: e,
+        }
+    })?;
+    Ok(sidecar)
+}
+
+/// Heuristic speaker index (0 or 1) for each segment: the speaker flips
+/// whenever the silence before a segment exceeds `gap` seconds.
+fn speaker_turns(segments: &[Segment], gap: f64) -> Vec<usize> {
+    let mut speaker = 0;
+    segments
+        .iter()
+        .enumerate()
+        .map(|(i, seg)| {
+            if i > 0 && seg.start - segments[i - 1].end > gap {
+                speaker = 1 - speaker;
+            }
+            speaker
+        })
+        .collect()
+}
+
+/// Gap in seconds before segment `i`, if it exceeds `threshold`.
+fn pause_before(segments: &[Segment], i: usize, threshold: Option<f64>) -> Option<f64> {
+    let threshold = threshold?;
+    let prev = segments.get(i.checked_sub(1)?)?;
+    let gap = segments[i].start - prev.end;
+    (gap > threshold).then_some(gap)
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::ScratchDir;
+    use crate::transcribe::TokenProb;
+
+    fn seg(start: f64, end: f64, text: &str) -> Segment {
+        Segment {
+            start,
+            end,
+            text: text.to_string(),
+            ..Segment::default()
+        }
+    }
+
+    fn render(segments: &[Segment], mark_pauses: Option<f64>) -> String {
+        let opts = TextOptions {
+            mark_pauses,
+            ..TextOptions::default()
+        };
+        render_text(Path::new("kayit.mp3"), "medium", 1.0, segments, &opts)
+    }
+
+    #[test]
+    fn pause_marker_only_when_gap_exceeds_threshold() {
+        let segments = [
+            seg(0.0, 2.0, "Birinci."),
+            seg(5.0, 6.0, "İkinci."),
+            seg(9.01, 10.0, "Üçüncü."),
+        ];
+        let out = render(&segments, Some(3.0));
+        // 3.0s gap is exactly the threshold — no marker
+        assert_eq!(out.matches("[pause:").count(), 1);
+        assert!(out.contains("[00:05 -> 00:06]  İkinci.\n[pause: 3s]\n[00:09 -> 00:10]  Üçüncü.\n"));
+        assert!(out.contains("Birinci. İkinci.\n\nÜçüncü.\n"));
+    }
+
+    #[test]
+    fn pause_duration_is_rounded() {
+        let segments = [seg(0.0, 1.0, "a"), seg(3.5, 4.0, "b"), seg(6.4, 7.0, "c")];
+        let out = render(&segments, Some(1.0));
+        assert!(out.contains("[pause: 3s]"));
+        assert!(out.contains("[pause: 2s]"));
+    }
+
+    #[test]
+    fn plain_format_is_bare_joined_text() {
+        let segments = [seg(0.0, 1.0, "Merhaba."), seg(1.0, 2.0, "Nasılsınız?")];
+        let opts = TextOptions::default().plain();
+        let out = render_text(Path::new("kayit.mp3"), "medium", 2.0, &segments, &opts);
+        assert!(!out.lines().any(|l| l.starts_with("===")));
+        assert_eq!(out, "Merhaba. Nasılsınız?\n");
+    }
+
+    #[test]
+    fn source_path_styles() {
+        let base = ScratchDir::new("paths");
+        let source = base.join("2024").join("durusma.mp3");
+        std::fs::create_dir_all(source.parent().unwrap()).unwrap();
+        std::fs::write(&source, b"").unwrap();
+
+        let name = source_path(&source, PathStyle::Name, &base);
+        let relative = source_path(&source, PathStyle::Relative, &base);
+        let absolute = source_path(&source, PathStyle::Absolute, &base);
+        let elsewhere = source_path(&source, PathStyle::Relative, &base.join("2023"));
+
+        assert_eq!(name, Path::new("durusma.mp3"));
+        assert_eq!(relative, Path::new("2024").join("durusma.mp3"));
+        assert!(absolute.is_absolute());
+        assert!(absolute.ends_with(Path::new("2024").join("durusma.mp3")));
+        assert_eq!(
+            elsewhere, absolute,
+            "outside the base falls back to absolute"
+        );
+    }
+
+    #[test]
+    fn header_shows_source_path_as_given() {
+        let segments = [seg(0.0, 1.0, "Merhaba.")];
+        let source = Path::new("2024").join("kayit.mp3");
+        let out = render_text(&source, "medium", 1.0, &segments, &TextOptions::default());
+        assert!(out.contains(&format!("Source: {}\n", source.display())));
+    }
+
+    #[test]
+    fn header_template_replaces_default_header() {
+        let segments = [seg(0.0, 1.0, "Merhaba.")];
+        let opts = TextOptions {
+            header_template: Some(
+                HeaderTemplate::parse("Case 42 — {source} [{language}]").unwrap(),
+            ),
+            ..TextOptions::default()
+        };
+        let out = render_text(Path::new("kayit.mp3"), "medium", 1.0, &segments, &opts);
+        assert!(out.starts_with("Case 42 — kayit.mp3 [tr]\n\nMerhaba.\n"));
+        assert!(!out.contains("=== TRANSCRIPT"));
+    }
+
+    fn render_speakers(segments: &[Segment], style: SpeakerStyle) -> String {
+        let opts = TextOptions {
+            speakers: Some(SpeakerMarks { style, gap: 1.5 }),
+            ..TextOptions::default()
+        };
+        render_text(Path::new("kayit.mp3"), "medium", 1.0, segments, &opts)
+    }
+
+    #[test]
+    fn speaker_turns_alternate_at_gaps() {
+        let segments = [
+            seg(0.0, 1.0, "a"),
+            seg(1.2, 2.0, "b"),
+            seg(4.0, 5.0, "c"),
+            seg(7.0, 8.0, "d"),
+            seg(8.5, 9.0, "e"),
+        ];
+        assert_eq!(speaker_turns(&segments, 1.5), vec![0, 0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn speaker_change_marker_placed_at_gap() {
+        let segments = [
+            seg(0.0, 1.0, "Merhaba."),
+            seg(1.2, 2.0, "Hoş geldiniz."),
+            seg(4.0, 5.0, "Teşekkürler."),
+        ];
+        let out = render_speakers(&segments, SpeakerStyle::Change);
+        assert!(out.contains("Merhaba. Hoş geldiniz.\n\n--- Speaker change ---\nTeşekkürler.\n"));
+        assert!(
+            out.contains("Hoş geldiniz.\n--- Speaker change ---\n[00:04 -> 00:05]  Teşekkürler.\n")
+        );
+        assert_eq!(out.matches(SPEAKER_CHANGE_MARKER).count(), 2);
+    }
+
+    #[test]
+    fn speaker_labels_alternate() {
+        let segments = [
+            seg(0.0, 1.0, "Soru?"),
+            seg(3.0, 4.0, "Cevap."),
+            seg(6.0, 7.0, "Peki."),
+        ];
+        let out = render_speakers(&segments, SpeakerStyle::Labels);
+        assert!(out.contains("Speaker 1: Soru?\n\nSpeaker 2: Cevap.\n\nSpeaker 1: Peki.\n"));
+        assert!(out.contains("[00:03 -> 00:04]  Speaker 2: Cevap.\n"));
+    }
+
+    #[test]
+    fn existing_file_rejected_without_flag() {
+        let dir = ScratchDir::new("protect");
+        let path = dir.join("out.txt");
+        std::fs::write(&path, "eski").unwrap();
+
+        assert!(matches!(
+            check_target(&path, WriteMode::Protect),
+            Err(OutputError::AlreadyExists { .. })
+        ));
+        assert!(matches!(
+            write_file(&path, "yeni", WriteMode::Protect, Encoding::Utf8),
+            Err(OutputError::AlreadyExists { .. })
+        ));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "eski");
+
+        write_file(&path, "yeni", WriteMode::Overwrite, Encoding::Utf8).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "yeni");
+    }
+
+    #[test]
+    fn append_adds_to_existing_file() {
+        let dir = ScratchDir::new("append");
+        let path = dir.join("out.txt");
+
+        write_file(&path, "birinci\n", WriteMode::Append, Encoding::Utf8).unwrap();
+        write_file(&path, "ikinci\n", WriteMode::Append, Encoding::Utf8).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "birinci\nikinci\n");
+    }
+
+    #[test]
+    fn bom_written_once_at_start_only_when_requested() {
+        let dir = ScratchDir::new("bom");
+        let path = dir.join("out.txt");
+        let bom_count = |bytes: &[u8]| bytes.windows(3).filter(|w| *w == UTF8_BOM).count();
+
+        write_file(&path, "ğüşıöç\n", WriteMode::Overwrite, Encoding::Utf8Bom).unwrap();
+        write_file(&path, "ikinci\n", WriteMode::Append, Encoding::Utf8Bom).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(&bytes[..3], [0xEF, 0xBB, 0xBF]);
+        assert_eq!(bom_count(&bytes), 1);
+        assert_eq!(
+            std::str::from_utf8(&bytes[3..]).unwrap(),
+            "ğüşıöç\nikinci\n"
+        );
+
+        write_file(&path, "ğüşıöç\n", WriteMode::Overwrite, Encoding::Utf8).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bom_count(&bytes), 0);
+        assert_eq!(bytes, "ğüşıöç\n".as_bytes());
+    }
+
+    #[test]
+    fn no_markers_without_option() {
+        let segments = [seg(0.0, 1.0, "a"), seg(30.0, 31.0, "b")];
+        let out = render(&segments, None);
+        assert!(!out.contains("[pause:"));
+        assert!(out.contains("a b\n"));
+    }
+
+    #[test]
+    fn two_formats_write_two_files() {
+        let dir = ScratchDir::new("multi");
+        let formats = vec!["txt".to_string(), "srt".to_string()];
+        let targets = targets_at(&dir.join("kayit_transcript.txt"), &formats);
+        assert_eq!(targets[0].path, dir.join("kayit_transcript.txt"));
+        assert_eq!(targets[1].path, dir.join("kayit_transcript.srt"));
+
+        let segments = [seg(0.0, 1.5, "Merhaba."), seg(2.0, 3.0, "Nasılsın?")];
+        let doc = Document {
+            source: Path::new("kayit.mp3"),
+            model_size: "medium",
+            duration: 1.0,
+            segments: &segments,
+        };
+        write_outputs(
+            &targets,
+            &doc,
+            &TextOptions::default(),
+            42,
+            WriteMode::Protect,
+            Encoding::Utf8,
+        )
+        .unwrap();
+
+        let txt = std::fs::read_to_string(&targets[0].path).unwrap();
+        assert!(txt.starts_with("=== TRANSCRIPT (Turkish) ==="));
+        assert!(txt.contains("Merhaba. Nasılsın?\n"));
+        let srt = std::fs::read_to_string(&targets[1].path).unwrap();
+        assert!(srt.starts_with("1\n00:00:00,000 --> 00:00:01,500\nMerhaba.\n"));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn json_lists_segments() {
+        let segments = [seg(0.0, 1.5, "Merhaba.")];
+        let doc = Document {
+            source: Path::new("kayit.mp3"),
+            model_size: "small",
+            duration: 2.0,
+            segments: &segments,
+        };
+        let value: serde_json::Value = serde_json::from_str(&render_json(&doc).unwrap()).unwrap();
+        assert_eq!(value["source"], "kayit.mp3");
+        assert_eq!(value["model"], "whisper-small");
+        assert_eq!(value["segments"][0]["end"], 1.5);
+        assert_eq!(value["segments"][0]["text"], "Merhaba.");
+    }
+
+    #[test]
+    fn json_records_styled_source_path() {
+        let base = ScratchDir::new("json-paths");
+        let source = base.join("2024").join("durusma.mp3");
+        std::fs::create_dir_all(source.parent().unwrap()).unwrap();
+        std::fs::write(&source, b"").unwrap();
+
+        let segments = [seg(0.0, 1.0, "Merhaba.")];
+        let json_source = |style| {
+            let styled = source_path(&source, style, &base);
+            let doc = Document {
+                source: &styled,
+                model_size: "small",
+                duration: 1.0,
+                segments: &segments,
+            };
+            let value: serde_json::Value =
+                serde_json::from_str(&render_json(&doc).unwrap()).unwrap();
+            (value["source"].as_str().unwrap().to_string(), styled)
+        };
+
+        let (relative, styled) = json_source(PathStyle::Relative);
+        assert_eq!(Path::new(&relative), Path::new("2024").join("durusma.mp3"));
+        assert_eq!(relative, styled.display().to_string());
+        let (absolute, styled) = json_source(PathStyle::Absolute);
+        assert!(Path::new(&absolute).is_absolute());
+        assert_eq!(absolute, styled.display().to_string());
+    }
+
+    #[test]
+    fn json_leaves_out_tokens() {
+        let segments = [Segment {
+            tokens: vec![TokenProb {
+                text: " Merhaba.".to_string(),
+                p: 0.9,
+            }],
+            ..seg(0.0, 1.5, "Merhaba.")
+        }];
+        let doc = Document {
+            source: Path::new("kayit.mp3"),
+            model_size: "small",
+            duration: 2.0,
+            segments: &segments,
+        };
+        let value: serde_json::Value = serde_json::from_str(&render_json(&doc).unwrap()).unwrap();
+        assert!(value["segments"][0].get("tokens").is_none());
+        assert!(value["segments"][0].get("language").is_none());
+    }
+
+    #[test]
+    fn byte_order_mark_only_on_text_formats() {
+        for format in ["txt", "plain", "lines", "review", "srt", "vtt"] {
+            assert_eq!(Encoding::Utf8Bom.for_format(format), Encoding::Utf8Bom);
+        }
+        assert_eq!(Encoding::Utf8Bom.for_format("json"), Encoding::Utf8);
+        assert_eq!(Encoding::Utf8.for_format("srt"), Encoding::Utf8);
+    }
+
+    #[test]
+    fn formats_sharing_an_extension_rejected() {
+        let formats = |list: &[&str]| list.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+        assert!(check_formats(&formats(&["txt", "srt", "json"])).is_ok());
+        assert!(check_formats(&formats(&["txt", "plain"])).is_err());
+        assert!(check_formats(&formats(&["srt", "srt"])).is_err());
+    }
+
+    #[test]
+    fn checksum_sidecar_matches_contents() {
+        let dir = ScratchDir::new("checksum");
+        let path = dir.join("kayit_transcript.txt");
+        std::fs::write(&path, "abc").unwrap();
+
+        let sidecar = write_checksum(&path).unwrap();
+        assert_eq!(sidecar, dir.join("kayit_transcript.txt.sha256"));
+        // Known SHA-256 of "abc" (FIPS 180-2 test vector)
+        assert_eq!(
+            std::fs::read_to_string(&sidecar).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  \
+             kayit_transcript.txt\n"
+        );
+    }
+
+    #[test]
+    fn missing_output_directory_is_created() {
+        let dir = ScratchDir::new("missing-parent");
+        let path = dir.join("yeni").join("alt").join("out.txt");
+        check_writable_dir(&path).unwrap();
+        assert!(dir.join("yeni").join("alt").is_dir());
+        assert_eq!(std::fs::read_dir(dir.join("yeni/alt")).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn parallel_probes_of_one_directory_all_pass() {
+        let dir = ScratchDir::new("parallel-probe");
+        let path = dir.join("out.txt");
+        std::thread::scope(|s| {
+            let probes: Vec<_> = (0..8)
+                .map(|_| s.spawn(|| check_writable_dir(&path)))
+                .collect();
+            for probe in probes {
+                assert!(probe.join().unwrap().is_ok());
+            }
+        });
+        assert_eq!(
+            std::fs::read_dir(&*dir).unwrap().count(),
+            0,
+            "probes removed"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_directory_rejected() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = ScratchDir::new("read-only");
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o555)).unwrap();
+        // Permission bits don't stop root; nothing to check then
+        let writable = std::fs::File::create(dir.join("root-probe")).is_ok();
+        let result = check_writable_dir(&dir.join("out.txt"));
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        if !writable {
+            assert!(matches!(result, Err(OutputError::DirNotWritable { .. })));
+        }
+    }
+}
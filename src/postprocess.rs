@@ -4,14 +4,81 @@
 //! Whisper errors for Turkish: missing question marks, garbled words,
 //! wrong special characters, and mangled proper nouns.
 
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::errors::ConfigError;
+use crate::fillers::Fillers;
+use crate::numbers::{self, NumberStyle};
+
+/// User-supplied settings for the optional post-processing passes.
+#[derive(Default)]
+pub struct Config {
+    /// Replacement rules loaded from `--replacements`
+    pub rules: Rules,
+    /// Words to mask, already lowercased with Turkish casing rules
+    pub mask_words: Vec<String>,
+    /// Replacement for masked words; `None` masks with asterisks
+    pub mask_token: Option<String>,
+    /// Normalize numbers to digits or words; `None` leaves them untouched
+    pub numbers: Option<NumberStyle>,
+    /// Filler sounds to strip (`--remove-fillers`); `None` keeps them
+    pub fillers: Option<Fillers>,
+}
+
 /// Apply all Turkish post-processing passes to a segment's text.
-pub fn process(text: &str) -> String {
+pub fn process(text: &str, config: &Config) -> String {
     let text = fix_substitutions(text);
+    let text = config.rules.apply(&text);
+    let text = match &config.fillers {
+        Some(fillers) => fillers.remove(&text),
+        None => text,
+    };
     let text = fix_proper_nouns(&text);
     let text = fix_turkish_chars(&text);
+    let text = match config.numbers {
+        Some(style) => numbers::normalize(&text, style),
+        None => text,
+    };
+    let text = mask_words(&text, &config.mask_words, config.mask_token.as_deref());
     fix_question_marks(&text)
 }
 
+/// Apply only the language-neutral passes (user rules, masking) to a
+/// segment in another language.
+pub fn process_neutral(text: &str, config: &Config) -> String {
+    let text = config.rules.apply(text);
+    mask_words(&text, &config.mask_words, config.mask_token.as_deref())
+}
+
+/// Read a word list: one entry per line, blank lines and `#` comments ignored.
+pub fn load_word_list(path: &Path) -> Result<Vec<String>, ConfigError> {
+    let contents = std::fs::read_to_string(path).map_err(|e| ConfigError::ReadFailed {
+        path: path.display().to_string(),
+        source: e,
+    })?;
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(turkish_lowercase)
+        .collect())
+}
+
+/// Lowercase with Turkish dotted/dotless I rules (`I` → `ı`, `İ` → `i`).
+pub(crate) fn turkish_lowercase(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            'I' => out.push('ı'),
+            'İ' => out.push('i'),
+            _ => out.extend(c.to_lowercase()),
+        }
+    }
+    out
+}
+
 // ── Question Particle Detection ─────────────────────────────────────
 
 /// Turkish question particles (all vowel-harmony variants).
@@ -35,7 +102,7 @@ fn fix_question_marks(text: &str) -> String {
     }
 
     // Strip trailing punctuation (.!,;:) to check the bare word
-    let stripped = trimmed.trim_end_matches(|c: char| matches!(c, '.' | '!' | ',' | ';' | ':'));
+    let stripped = trimmed.trim_end_matches(['.', '!', ',', ';', ':']);
 
     let lower = stripped.to_lowercase();
     for particle in QUESTION_PARTICLES {
@@ -74,6 +141,76 @@ fn fix_substitutions(text: &str) -> String {
     result
 }
 
+// ── User Replacement Rules ──────────────────────────────────────────
+
+/// User replacement rules, one `from => to` per line. Rules whose `from`
+/// starts with `regex:` are compiled as regular expressions (`$1` etc. in
+/// `to` refer to capture groups); all others are literal.
+#[derive(Default)]
+pub struct Rules {
+    literal: Vec<(String, String)>,
+    regex: Vec<(Regex, String)>,
+}
+
+impl Rules {
+    /// Load and compile rules from a file.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|e| ConfigError::ReadFailed {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        Self::parse(&contents, &path.display().to_string())
+    }
+
+    /// Parse rules, compiling every regex up front. `source` names the
+    /// rules file in error messages.
+    fn parse(contents: &str, source: &str) -> Result<Self, ConfigError> {
+        let mut rules = Self::default();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim_start();
+            if line.trim_end().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |reason: String| ConfigError::InvalidRule {
+                path: source.to_string(),
+                line: i + 1,
+                reason,
+            };
+
+            let (from, to) = line
+                .split_once(" =>")
+                .ok_or_else(|| invalid("expected `from => to`".to_string()))?;
+            // Only the pattern is trimmed, so `from =>` deletes `from`
+            let from = from.trim_end();
+            let to = to.strip_prefix(' ').unwrap_or(to);
+
+            match from.strip_prefix("regex:") {
+                Some(pattern) => {
+                    let re = Regex::new(pattern).map_err(|e| invalid(e.to_string()))?;
+                    rules.regex.push((re, to.to_string()));
+                }
+                None => rules.literal.push((from.to_string(), to.to_string())),
+            }
+        }
+
+        Ok(rules)
+    }
+
+    /// Apply literal rules first, then regex rules, each in file order.
+    fn apply(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        for (from, to) in &self.literal {
+            result = result.replace(from.as_str(), to);
+        }
+        for (re, to) in &self.regex {
+            result = re.replace_all(&result, to.as_str()).into_owned();
+        }
+        result
+    }
+}
+
 // ── Turkish Character Normalization ──────────────────────────────────
 
 /// Fix common Whisper outputs that use wrong Turkish special characters.
@@ -108,6 +245,51 @@ fn fix_proper_nouns(text: &str) -> String {
     result
 }
 
+// ── Word Masking ────────────────────────────────────────────────────
+
+/// Replace whole-word, case-insensitive matches of `words` with `token`, or
+/// with asterisks of equal length when no token is given.
+///
+/// Word boundaries are any non-alphanumeric character, so a listed word
+/// inside a longer word is left alone while `Ankara'da` still matches
+/// `ankara`.
+fn mask_words(text: &str, words: &[String], token: Option<&str>) -> String {
+    if words.is_empty() {
+        return text.to_string();
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut word = String::new();
+
+    let flush = |word: &mut String, result: &mut String| {
+        if word.is_empty() {
+            return;
+        }
+        let lower = turkish_lowercase(word);
+        if words.contains(&lower) {
+            match token {
+                Some(t) => result.push_str(t),
+                None => result.extend(std::iter::repeat_n('*', word.chars().count())),
+            }
+        } else {
+            result.push_str(word);
+        }
+        word.clear();
+    };
+
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut result);
+            result.push(c);
+        }
+    }
+    flush(&mut word, &mut result);
+
+    result
+}
+
 // ── Tests ───────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -157,10 +339,71 @@ mod tests {
         assert_eq!(fix_turkish_chars("hültür değişimi"), "kültür değişimi");
     }
 
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| turkish_lowercase(w)).collect()
+    }
+
+    #[test]
+    fn mask_whole_word_with_asterisks() {
+        let list = words(&["lanet"]);
+        assert_eq!(mask_words("Bu lanet iş bitti", &list, None), "Bu ***** iş bitti");
+        assert_eq!(mask_words("lanet, dedi", &list, None), "*****, dedi");
+    }
+
+    #[test]
+    fn mask_is_case_insensitive() {
+        let list = words(&["lanet", "ırmak"]);
+        assert_eq!(mask_words("LANET olsun", &list, None), "***** olsun");
+        // Turkish dotless capital I lowercases to ı
+        assert_eq!(mask_words("IRMAK taştı", &list, Some("[x]")), "[x] taştı");
+    }
+
+    #[test]
+    fn mask_does_not_match_substrings() {
+        let list = words(&["kör"]);
+        assert_eq!(mask_words("körfez güzel", &list, None), "körfez güzel");
+        assert_eq!(mask_words("Kör'ün gözü", &list, None), "***'ün gözü");
+    }
+
+    #[test]
+    fn literal_rule_replaces_text() {
+        let rules = Rules::parse("# comment\ntoplanti => toplantı\n", "rules.txt").unwrap();
+        assert_eq!(rules.apply("toplanti başladı"), "toplantı başladı");
+    }
+
+    #[test]
+    fn regex_rule_uses_capture_groups() {
+        let rules = Rules::parse(r"regex:(\d+)\s*% => %$1", "rules.txt").unwrap();
+        assert_eq!(rules.apply("satışlar 50 % arttı"), "satışlar %50 arttı");
+    }
+
+    #[test]
+    fn literal_rules_run_before_regex_rules() {
+        let rules = Rules::parse("regex:b+ => c\na => b\n", "rules.txt").unwrap();
+        assert_eq!(rules.apply("a"), "c");
+    }
+
+    #[test]
+    fn empty_replacement_deletes_the_match() {
+        let rules = Rules::parse("regex:\\s*\\bhani\\b =>\neee => \n", "rules.txt").unwrap();
+        assert_eq!(rules.apply("geldi hani eee dün"), "geldi  dün");
+    }
+
+    #[test]
+    fn invalid_regex_reports_line_number() {
+        let err = Rules::parse("a => b\n\nregex:(unclosed => x\n", "rules.txt")
+            .err()
+            .expect("invalid regex must fail");
+        match err {
+            ConfigError::InvalidRule { line, .. } => assert_eq!(line, 3),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
     #[test]
     fn full_pipeline() {
         let input = "Peter Dubek hültür değişimi hakkında mı.";
-        let output = process(input);
+        let output = process(input, &Config::default());
         assert_eq!(output, "Peter Drucker kültür değişimi hakkında mı?");
     }
 }
@@ -0,0 +1,134 @@
+//! Per-model decoding defaults.
+//!
+//! Smaller models gain more from a wide beam; large-v3 is accurate enough
+//! that a narrow beam costs little and saves a lot of time. Values given
+//! on the command line always win over the profile.
+
+use tracing::info;
+
+use crate::model;
+
+/// Built-in decoding defaults for one model size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Profile {
+    pub model: &'static str,
+    /// Beam width for the first pass
+    pub beam_size: i32,
+    /// Temperature step for whisper.cpp's fallback when a window decodes badly
+    pub temperature_inc: f32,
+    /// No-speech probability above which a window is treated as silence
+    pub no_speech_thold: f32,
+}
+
+/// Profiles keyed by model size. Quantized variants use their size's row.
+pub static PROFILES: &[Profile] = &[
+    Profile {
+        model: "tiny",
+        beam_size: 8,
+        temperature_inc: 0.2,
+        no_speech_thold: 0.6,
+    },
+    Profile {
+        model: "base",
+        beam_size: 6,
+        temperature_inc: 0.2,
+        no_speech_thold: 0.6,
+    },
+    Profile {
+        model: "small",
+        beam_size: 5,
+        temperature_inc: 0.2,
+        no_speech_thold: 0.6,
+    },
+    Profile {
+        model: "medium",
+        beam_size: 5,
+        temperature_inc: 0.2,
+        no_speech_thold: 0.6,
+    },
+    Profile {
+        model: "large-v3",
+        beam_size: 3,
+        temperature_inc: 0.4,
+        no_speech_thold: 0.5,
+    },
+];
+
+/// Profile used for a model not in the table.
+const FALLBACK: Profile = Profile {
+    model: "default",
+    beam_size: 5,
+    temperature_inc: 0.2,
+    no_speech_thold: 0.6,
+};
+
+/// Decoding values given explicitly on the command line.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Overrides {
+    pub beam_size: Option<i32>,
+    pub temperature_inc: Option<f32>,
+    pub no_speech_thold: Option<f32>,
+}
+
+/// The profile for `model`, which may carry a quantization suffix.
+pub fn for_model(model: &str) -> Profile {
+    let size = model::base_size(model);
+    PROFILES
+        .iter()
+        .find(|p| p.model == size)
+        .copied()
+        .unwrap_or(FALLBACK)
+}
+
+/// `model`'s profile with `overrides` applied; logs what was used.
+pub fn resolve(model: &str, overrides: &Overrides) -> Profile {
+    let base = for_model(model);
+    let profile = Profile {
+        beam_size: overrides.beam_size.unwrap_or(base.beam_size),
+        temperature_inc: overrides.temperature_inc.unwrap_or(base.temperature_inc),
+        no_speech_thold: overrides.no_speech_thold.unwrap_or(base.no_speech_thold),
+        ..base
+    };
+    info!(
+        profile = profile.model,
+        beam_size = profile.beam_size,
+        temperature_inc = profile.temperature_inc,
+        no_speech_thold = profile.no_speech_thold,
+        overridden = ?overrides,
+        "Decoding profile applied"
+    );
+    profile
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_model_gets_its_profile() {
+        for size in ["tiny", "base", "small", "medium", "large-v3"] {
+            let profile = resolve(size, &Overrides::default());
+            assert_eq!(profile.model, size);
+            assert_eq!(Some(&profile), PROFILES.iter().find(|p| p.model == size));
+        }
+        assert_eq!(for_model("tiny").beam_size, 8);
+        assert_eq!(for_model("large-v3").beam_size, 3);
+        assert_eq!(for_model("large-v3-q5_0"), for_model("large-v3"));
+        assert_eq!(for_model("custom").model, "default");
+    }
+
+    #[test]
+    fn explicit_flags_override_profile() {
+        let overrides = Overrides {
+            beam_size: Some(1),
+            no_speech_thold: Some(0.9),
+            ..Default::default()
+        };
+        let profile = resolve("tiny", &overrides);
+        assert_eq!(profile.beam_size, 1);
+        assert_eq!(profile.no_speech_thold, 0.9);
+        assert_eq!(profile.temperature_inc, for_model("tiny").temperature_inc);
+    }
+}
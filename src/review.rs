@@ -0,0 +1,265 @@
+//! `--format review`: the full text with low-confidence words marked,
+//! followed by the least confident segments, as an aid for editors.
+//!
+//! Words are rebuilt from Whisper's tokens (a token starting with a space
+//! starts a new word) and scored by their least probable token, then
+//! aligned with the final text: words post-processing rewrote (spelling
+//! fixes, numbers, replacements) take the lowest score of the words they
+//! replaced. Runs of words below the threshold are wrapped in `⟨ ⟩`.
+
+use std::fmt::Write as _;
+
+use crate::output::Document;
+use crate::transcribe::{Segment, TokenProb};
+
+/// Words with a token probability below this are marked.
+pub const LOW_PROB: f32 = 0.5;
+
+/// Number of segments listed in the summary.
+const SUMMARY_LEN: usize = 10;
+
+const OPEN: char = '⟨';
+const CLOSE: char = '⟩';
+
+/// Words of `tokens`, each with its lowest token probability.
+fn words(tokens: &[TokenProb]) -> Vec<(String, f32)> {
+    let mut words: Vec<(String, f32)> = Vec::new();
+    for token in tokens {
+        match words.last_mut() {
+            Some((word, p)) if !token.text.starts_with(char::is_whitespace) => {
+                word.push_str(&token.text);
+                *p = p.min(token.p);
+            }
+            _ => words.push((token.text.trim_start().to_string(), token.p)),
+        }
+    }
+    words.retain(|(word, _)| !word.is_empty());
+    words
+}
+
+/// Score each word of `text` from the token `words` it came from. Words
+/// left unchanged are matched up (longest common subsequence) and keep
+/// their own score; each stretch of `text` between matches takes the
+/// lowest score of the token words in the same stretch, or counts as
+/// certain if post-processing added it.
+fn align<'a>(text: &'a str, words: &[(String, f32)]) -> Vec<(&'a str, f32)> {
+    let text: Vec<&str> = text.split_whitespace().collect();
+    let (n, m) = (text.len(), words.len());
+    // common[i][j]: length of the common subsequence of text[i..], words[j..]
+    let mut common = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            common[i][j] = if text[i] == words[j].0 {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut scored = Vec::with_capacity(n);
+    let (mut i, mut j) = (0, 0);
+    let (mut gap_start, mut gap_p) = (0, 1.0f32);
+    loop {
+        if i < n && j < m && text[i] == words[j].0 {
+            scored.extend(text[gap_start..i].iter().map(|w| (*w, gap_p)));
+            scored.push((text[i], words[j].1));
+            (i, j) = (i + 1, j + 1);
+            (gap_start, gap_p) = (i, 1.0);
+        } else if j < m && (i == n || common[i][j + 1] >= common[i + 1][j]) {
+            gap_p = gap_p.min(words[j].1);
+            j += 1;
+        } else if i < n {
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    scored.extend(text[gap_start..].iter().map(|w| (*w, gap_p)));
+    scored
+}
+
+/// `seg`'s text with runs of words below `threshold` wrapped in `⟨ ⟩`.
+pub fn mark_segment(seg: &Segment, threshold: f32) -> String {
+    let tokens = words(&seg.tokens);
+    if tokens.is_empty() {
+        return seg.text.clone();
+    }
+    let words = align(&seg.text, &tokens);
+
+    let mut out = String::with_capacity(seg.text.len() + 8);
+    let mut open = false;
+    for (i, &(word, p)) in words.iter().enumerate() {
+        let low = p < threshold;
+        if i > 0 {
+            if open && !low {
+                out.push(CLOSE);
+                open = false;
+            }
+            out.push(' ');
+        }
+        if low && !open {
+            out.push(OPEN);
+            open = true;
+        }
+        out.push_str(word);
+    }
+    if open {
+        out.push(CLOSE);
+    }
+    out
+}
+
+/// Up to `n` scored segments, lowest confidence first.
+pub fn lowest_segments(segments: &[Segment], n: usize) -> Vec<&Segment> {
+    let mut scored: Vec<&Segment> = segments.iter().filter(|s| s.confidence.is_some()).collect();
+    scored.sort_by(|a, b| {
+        a.confidence
+            .unwrap_or_default()
+            .total_cmp(&b.confidence.unwrap_or_default())
+            .then(a.start.total_cmp(&b.start))
+    });
+    scored.truncate(n);
+    scored
+}
+
+/// Render the review document.
+pub fn render_review(doc: &Document) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "=== REVIEW ===");
+    let _ = writeln!(out, "Source: {}", doc.source.display());
+    let _ = writeln!(out, "Model: whisper-{}", doc.model_size);
+    let _ = writeln!(
+        out,
+        "Words below {:.0}% confidence are marked {OPEN}like this{CLOSE}.",
+        LOW_PROB * 100.0
+    );
+    let _ = writeln!(out, "{}", "=".repeat(40));
+    out.push('\n');
+
+    if doc.segments.is_empty() {
+        out.push_str("No speech detected in the audio.\n");
+        return out;
+    }
+
+    let text = doc
+        .segments
+        .iter()
+        .map(|seg| mark_segment(seg, LOW_PROB))
+        .collect::<Vec<_>>()
+        .join(" ");
+    out.push_str(&text);
+    out.push('\n');
+
+    let lowest = lowest_segments(doc.segments, SUMMARY_LEN);
+    if lowest.is_empty() {
+        return out;
+    }
+    out.push_str("\n=== LOWEST CONFIDENCE ===\n\n");
+    for seg in lowest {
+        let (sm, ss) = (seg.start as u64 / 60, seg.start as u64 % 60);
+        let (em, es) = (seg.end as u64 / 60, seg.end as u64 % 60);
+        let _ = writeln!(
+            out,
+            "[{sm:02}:{ss:02} -> {em:02}:{es:02}]  {:>3.0}%  {}",
+            seg.confidence.unwrap_or_default() * 100.0,
+            seg.text
+        );
+    }
+    out
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn token(text: &str, p: f32) -> TokenProb {
+        TokenProb {
+            text: text.to_string(),
+            p,
+        }
+    }
+
+    fn seg(start: f64, text: &str, confidence: Option<f32>, tokens: Vec<TokenProb>) -> Segment {
+        Segment {
+            start,
+            end: start + 2.0,
+            text: text.to_string(),
+            confidence,
+            tokens,
+            ..Segment::default()
+        }
+    }
+
+    #[test]
+    fn low_probability_words_wrapped() {
+        let tokens = vec![
+            token(" Toplantı", 0.95),
+            token(" sal", 0.9),
+            token("ı", 0.3), // one weak token marks the whole word
+            token(" günü", 0.4),
+            token(" saat", 0.9),
+            token(" üçte", 0.2),
+            token(".", 0.99),
+        ];
+        let segment = seg(0.0, "Toplantı salı günü saat üçte.", Some(0.6), tokens);
+        assert_eq!(
+            mark_segment(&segment, 0.5),
+            "Toplantı ⟨salı günü⟩ saat ⟨üçte.⟩"
+        );
+        assert_eq!(mark_segment(&segment, 0.1), "Toplantı salı günü saat üçte.");
+    }
+
+    #[test]
+    fn post_processed_words_keep_their_scores() {
+        let tokens = vec![
+            token(" ıı", 0.1),
+            token(" toplanti", 0.2),
+            token(" yirmi", 0.3),
+            token(" üç", 0.9),
+            token(" kişi", 0.9),
+            token(" geldi", 0.4),
+        ];
+        // Filler dropped, spelling fixed, number converted
+        let segment = seg(0.0, "Toplantı 23 kişi geldi", Some(0.5), tokens);
+        assert_eq!(mark_segment(&segment, 0.5), "⟨Toplantı 23⟩ kişi ⟨geldi⟩");
+
+        let tokens = vec![token(" merhaba", 0.9)];
+        let segment = seg(0.0, "merhaba arkadaşlar", Some(0.9), tokens);
+        assert_eq!(mark_segment(&segment, 0.5), "merhaba arkadaşlar");
+        assert_eq!(mark_segment(&seg(0.0, "boş", None, Vec::new()), 0.5), "boş");
+    }
+
+    #[test]
+    fn summary_lists_lowest_confidence_first() {
+        let segments = [
+            seg(0.0, "iyi", Some(0.9), Vec::new()),
+            seg(2.0, "kötü", Some(0.2), Vec::new()),
+            seg(4.0, "puansız", None, Vec::new()),
+            seg(6.0, "orta", Some(0.5), Vec::new()),
+            seg(8.0, "yine kötü", Some(0.2), Vec::new()),
+        ];
+        let texts: Vec<&str> = lowest_segments(&segments, 3)
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect();
+        assert_eq!(texts, vec!["kötü", "yine kötü", "orta"]);
+
+        let doc = Document {
+            source: Path::new("kayit.mp3"),
+            model_size: "medium",
+            duration: 10.0,
+            segments: &segments,
+        };
+        let out = render_review(&doc);
+        let summary = out.split("=== LOWEST CONFIDENCE ===").nth(1).unwrap();
+        assert!(summary
+            .trim_start()
+            .starts_with("[00:02 -> 00:04]   20%  kötü\n"));
+        assert!(!summary.contains("puansız"));
+    }
+}
@@ -0,0 +1,284 @@
+//! Segment-level passes that run on the final segment list.
+//!
+//! These operate on whole segments rather than their text. The confidence
+//! filter runs after text post-processing and after any segment merging,
+//! so a merged segment is judged once on its combined confidence and the
+//! `[low confidence]` marker is never rewritten by the text passes.
+//! The repetition filter runs first, on Whisper's raw text. Timestamp
+//! tightening runs during collection, on each segment's token timings.
+
+use std::io::Write as _;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use tracing::{info, warn};
+
+use crate::console::status;
+use crate::transcribe::Segment;
+
+/// Marker appended to segments flagged by `--flag-below`.
+pub const LOW_CONFIDENCE_MARKER: &str = "[low confidence]";
+
+/// Thresholds on a segment's average token probability.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConfidenceFilter {
+    /// Remove segments below this probability (`--drop-below`)
+    pub drop_below: Option<f32>,
+    /// Mark segments below this probability (`--flag-below`)
+    pub flag_below: Option<f32>,
+}
+
+impl ConfidenceFilter {
+    fn is_active(&self) -> bool {
+        self.drop_below.is_some() || self.flag_below.is_some()
+    }
+}
+
+/// Drop, then flag, low-confidence segments. A segment exactly at a
+/// threshold is kept unmarked; segments without a confidence score are
+/// never touched.
+pub fn filter_confidence(segments: &mut Vec<Segment>, filter: &ConfidenceFilter) {
+    if !filter.is_active() {
+        return;
+    }
+    let below = |seg: &Segment, threshold: Option<f32>| match (seg.confidence, threshold) {
+        (Some(p), Some(t)) => p < t,
+        _ => false,
+    };
+
+    let mut dropped: Vec<String> = Vec::new();
+    segments.retain(|seg| {
+        let drop = below(seg, filter.drop_below);
+        if drop {
+            dropped.push(format!("{:.1}s-{:.1}s", seg.start, seg.end));
+        }
+        !drop
+    });
+    if !dropped.is_empty() {
+        info!(
+            count = dropped.len(),
+            ranges = %dropped.join(", "),
+            "Dropped low-confidence segments"
+        );
+        status!("       Dropped {} low-confidence segments", dropped.len());
+    }
+
+    let mut flagged = 0usize;
+    for seg in segments.iter_mut().filter(|s| below(s, filter.flag_below)) {
+        seg.text.push(' ');
+        seg.text.push_str(LOW_CONFIDENCE_MARKER);
+        flagged += 1;
+    }
+    if flagged > 0 {
+        info!(count = flagged, "Flagged low-confidence segments");
+    }
+}
+
+/// Timing of one token in a segment, in seconds.
+#[derive(Clone, Copy, Debug)]
+pub struct TokenSpan {
+    pub t0: f64,
+    pub t1: f64,
+    /// Text token, as opposed to a timestamp or other special token
+    pub speech: bool,
+}
+
+/// `--tighten-timestamps`: shrink `seg` to run from its first text token's
+/// start to its last text token's end, cutting the silence Whisper tends
+/// to include after the last word. Never widens a segment. Returns
+/// `false` and leaves `seg` alone when the tokens carry no usable timing.
+pub fn tighten(seg: &mut Segment, tokens: &[TokenSpan]) -> bool {
+    let timed = |t: &&TokenSpan| t.speech && t.t0 >= 0.0 && t.t1 >= t.t0;
+    let (Some(first), Some(last)) = (tokens.iter().find(timed), tokens.iter().rev().find(timed))
+    else {
+        return false;
+    };
+    let start = first.t0.max(seg.start);
+    let end = last.t1.min(seg.end);
+    if end <= start {
+        return false;
+    }
+    seg.start = start;
+    seg.end = end;
+    true
+}
+
+/// Whisper's own threshold for "too repetitive" decoder output.
+pub const DEFAULT_MAX_COMPRESSION_RATIO: f32 = 2.4;
+
+/// Size of `text` divided by its zlib-compressed size. Normal speech
+/// stays well below 2; a phrase looped many times compresses far better.
+pub fn compression_ratio(text: &str) -> f32 {
+    if text.is_empty() {
+        return 0.0;
+    }
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // Writing to a Vec cannot fail
+    let _ = encoder.write_all(text.as_bytes());
+    let compressed = encoder.finish().map(|c| c.len()).unwrap_or(text.len());
+    text.len() as f32 / compressed.max(1) as f32
+}
+
+/// Drop segments whose compression ratio is above `max_ratio`: looped
+/// hallucinations within a single segment.
+pub fn drop_repetitive(segments: &mut Vec<Segment>, max_ratio: f32) {
+    let before = segments.len();
+    segments.retain(|seg| {
+        let ratio = compression_ratio(&seg.text);
+        if ratio > max_ratio {
+            warn!(
+                ratio = format!("{ratio:.2}"),
+                range = format!("{:.1}s-{:.1}s", seg.start, seg.end),
+                "Dropped repetitive segment (likely hallucination)"
+            );
+        }
+        ratio <= max_ratio
+    });
+    let dropped = before - segments.len();
+    if dropped > 0 {
+        status!("       Dropped {dropped} repetitive segments (likely hallucinations)");
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seg(start: f64, text: &str, confidence: Option<f32>) -> Segment {
+        Segment {
+            start,
+            end: start + 1.0,
+            text: text.to_string(),
+            confidence,
+            ..Segment::default()
+        }
+    }
+
+    fn texts(segments: &[Segment]) -> Vec<&str> {
+        segments.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn drop_removes_segments_below_threshold() {
+        let mut segments = vec![
+            seg(0.0, "net", Some(0.9)),
+            seg(1.0, "bulanık", Some(0.2)),
+            seg(2.0, "bilinmiyor", None),
+        ];
+        let filter = ConfidenceFilter {
+            drop_below: Some(0.5),
+            ..Default::default()
+        };
+        filter_confidence(&mut segments, &filter);
+        assert_eq!(texts(&segments), vec!["net", "bilinmiyor"]);
+    }
+
+    #[test]
+    fn flag_marks_segments_below_threshold() {
+        let mut segments = vec![seg(0.0, "net", Some(0.9)), seg(1.0, "bulanık", Some(0.2))];
+        let filter = ConfidenceFilter {
+            flag_below: Some(0.5),
+            ..Default::default()
+        };
+        filter_confidence(&mut segments, &filter);
+        assert_eq!(texts(&segments), vec!["net", "bulanık [low confidence]"]);
+    }
+
+    #[test]
+    fn segment_at_threshold_is_kept() {
+        let mut segments = vec![seg(0.0, "sınırda", Some(0.5))];
+        let filter = ConfidenceFilter {
+            drop_below: Some(0.5),
+            flag_below: Some(0.5),
+        };
+        filter_confidence(&mut segments, &filter);
+        assert_eq!(texts(&segments), vec!["sınırda"]);
+    }
+
+    #[test]
+    fn looped_text_compresses_far_better_than_speech() {
+        let normal = "Bugünkü toplantıda bütçe taslağını, yeni personel alımını ve \
+                      önümüzdeki çeyreğin satış hedeflerini konuştuk.";
+        let looped = "Altyazı M.K. ".repeat(20);
+        let normal_ratio = compression_ratio(normal);
+        let looped_ratio = compression_ratio(&looped);
+        assert!(normal_ratio < 1.5, "normal text: {normal_ratio}");
+        assert!(
+            looped_ratio > DEFAULT_MAX_COMPRESSION_RATIO,
+            "looped text: {looped_ratio}"
+        );
+        assert_eq!(compression_ratio(""), 0.0);
+    }
+
+    #[test]
+    fn repetitive_segments_dropped_above_threshold() {
+        let looped = "teşekkürler ".repeat(30);
+        let mut segments = vec![
+            seg(0.0, "Herkese merhaba, hoş geldiniz.", None),
+            seg(1.0, looped.trim(), None),
+            seg(2.0, "Evet, evet.", None),
+        ];
+        drop_repetitive(&mut segments, DEFAULT_MAX_COMPRESSION_RATIO);
+        assert_eq!(
+            texts(&segments),
+            vec!["Herkese merhaba, hoş geldiniz.", "Evet, evet."]
+        );
+
+        // A looser threshold keeps it
+        let mut segments = vec![seg(0.0, looped.trim(), None)];
+        drop_repetitive(&mut segments, 100.0);
+        assert_eq!(segments.len(), 1);
+    }
+
+    fn span(t0: f64, t1: f64, speech: bool) -> TokenSpan {
+        TokenSpan { t0, t1, speech }
+    }
+
+    #[test]
+    fn tighten_clamps_to_text_tokens() {
+        let mut segment = Segment {
+            end: 5.0,
+            ..seg(0.8, "Merhaba dünya", None)
+        };
+        let tokens = [
+            span(0.8, 1.0, false), // leading timestamp token
+            span(1.0, 1.4, true),
+            span(1.4, 2.1, true),
+            span(2.1, 4.8, false), // trailing silence
+        ];
+        assert!(tighten(&mut segment, &tokens));
+        assert_eq!((segment.start, segment.end), (1.0, 2.1));
+
+        // Token timings outside the segment never widen it
+        let mut segment = seg(1.0, "kısa", None);
+        assert!(tighten(&mut segment, &[span(0.5, 2.5, true)]));
+        assert_eq!((segment.start, segment.end), (1.0, 2.0));
+    }
+
+    #[test]
+    fn tighten_leaves_untimed_segments_alone() {
+        let mut segment = seg(3.0, "zamansız", None);
+        // Token timestamps disabled: whisper.cpp reports zeros
+        assert!(!tighten(&mut segment, &[span(0.0, 0.0, true)]));
+        assert!(!tighten(&mut segment, &[span(3.0, 3.5, false)]));
+        assert!(!tighten(&mut segment, &[]));
+        assert_eq!((segment.start, segment.end), (3.0, 4.0));
+    }
+
+    #[test]
+    fn drop_applies_before_flag() {
+        let mut segments = vec![
+            seg(0.0, "çok kötü", Some(0.1)),
+            seg(1.0, "şüpheli", Some(0.4)),
+            seg(2.0, "iyi", Some(0.8)),
+        ];
+        let filter = ConfidenceFilter {
+            drop_below: Some(0.2),
+            flag_below: Some(0.5),
+        };
+        filter_confidence(&mut segments, &filter);
+        assert_eq!(texts(&segments), vec!["şüpheli [low confidence]", "iyi"]);
+    }
+}
@@ -0,0 +1,208 @@
+//! `selftest` subcommand: time the full pipeline on a short synthetic
+//! clip so users can see how fast their machine is before starting a
+//! long file.
+//!
+//! The clip is a voice-like buzz, not speech, so the transcript itself is
+//! meaningless; only the timings matter.
+
+use std::f32::consts::TAU;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use tracing::{debug, info};
+
+use crate::errors::OutputError;
+use crate::output;
+use crate::transcribe::{self, Metrics};
+
+/// Sample rate of the generated clip.
+const SAMPLE_RATE: usize = 16_000;
+
+/// Length of the generated clip in seconds.
+pub const CLIP_SECS: usize = 10;
+
+/// Fundamental frequency of the synthetic voice (Hz).
+const PITCH_HZ: f32 = 140.0;
+
+/// Syllable rate used to modulate the amplitude (Hz).
+const SYLLABLE_HZ: f32 = 4.0;
+
+/// Generate `secs` seconds of voice-like audio: a harmonic buzz with
+/// syllable-rate amplitude modulation and a short pause every 2 s.
+pub fn synthetic_clip(secs: usize) -> Vec<f32> {
+    (0..secs * SAMPLE_RATE)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            if t % 2.0 > 1.7 {
+                return 0.0;
+            }
+            let voice: f32 = (1..=8)
+                .map(|h| (TAU * PITCH_HZ * h as f32 * t).sin() / h as f32)
+                .sum();
+            let envelope = 0.5 - 0.5 * (TAU * SYLLABLE_HZ * t).cos();
+            0.2 * voice * envelope
+        })
+        .collect()
+}
+
+/// Transcribe the synthetic clip with `model_size` and print a timing
+/// report. The transcript is written to a temporary directory, which is
+/// removed with everything in it.
+pub fn run(model_size: &str, opts: &transcribe::Options) -> Result<Metrics> {
+    let metrics = run_with(model_size, |label, samples, targets| {
+        transcribe::run_samples(label, samples, model_size, targets, opts)
+    })?;
+    print!("{}", report(model_size, &metrics));
+    Ok(metrics)
+}
+
+/// Drive `pipeline` with the synthetic clip and a temporary output
+/// target, so the wiring can be tested without a Whisper model.
+fn run_with(
+    model_size: &str,
+    pipeline: impl FnOnce(&Path, Vec<f32>, &[output::Target]) -> Result<Metrics>,
+) -> Result<Metrics> {
+    let dir = TempDir::create()?;
+    let targets = [output::Target {
+        format: "txt".to_string(),
+        path: dir.0.join("selftest.txt"),
+    }];
+    info!(model = model_size, secs = CLIP_SECS, "Running self-test");
+
+    pipeline(
+        Path::new("selftest.wav"),
+        synthetic_clip(CLIP_SECS),
+        &targets,
+    )
+}
+
+/// A new, empty directory under the system temp dir for the self-test
+/// output (and its checksum sidecar), removed when dropped. A name that
+/// already exists is never reused.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn create() -> Result<Self, OutputError> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        let mut attempt = 0;
+        loop {
+            let path = std::env::temp_dir().join(format!(
+                "transcriber-selftest-{}-{nanos:x}-{attempt}",
+                std::process::id()
+            ));
+            match std::fs::create_dir(&path) {
+                Ok(()) => return Ok(Self(path)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && attempt < 100 => {
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return Err(OutputError::DirNotWritable {
+                        path: path.display().to_string(),
+                        source: e,
+                    })
+                }
+            }
+        }
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.0) {
+            debug!(path = %self.0.display(), "Self-test output not removed: {e}");
+        }
+    }
+}
+
+/// Human-readable timing report.
+fn report(model_size: &str, metrics: &Metrics) -> String {
+    let s = &metrics.stages;
+    let speed = if metrics.realtime_factor > 0.0 {
+        format!(
+            " ({:.1}x faster than realtime)",
+            1.0 / metrics.realtime_factor
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        "Self-test: whisper-{model_size}, {:.1}s synthetic clip\n\
+         \x20 Resolve model: {:>7.2}s\n\
+         \x20 Decode audio:  {:>7.2}s\n\
+         \x20 Load model:    {:>7.2}s\n\
+         \x20 Transcribe:    {:>7.2}s\n\
+         \x20 Post-process:  {:>7.2}s\n\
+         \x20 Write output:  {:>7.2}s\n\
+         Realtime factor: {:.2}x{speed}\n",
+        metrics.audio_duration_secs,
+        s.resolve_model_secs,
+        s.decode_secs,
+        s.load_model_secs,
+        s.transcribe_secs,
+        s.postprocess_secs,
+        s.write_secs,
+        metrics.realtime_factor,
+    )
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcribe::StageTimings;
+
+    #[test]
+    fn clip_is_audible_with_pauses() {
+        let clip = synthetic_clip(2);
+        assert_eq!(clip.len(), 2 * SAMPLE_RATE);
+        assert!(clip.iter().all(|s| s.abs() <= 1.0));
+        assert!(crate::audio::rms(&clip) > 0.01);
+        assert_eq!(clip[(1.8 * SAMPLE_RATE as f32) as usize], 0.0);
+    }
+
+    #[test]
+    fn selftest_runs_pipeline_and_returns_metrics() {
+        let mut written = None;
+        let metrics = run_with("tiny", |label, samples, targets| {
+            assert_eq!(label, Path::new("selftest.wav"));
+            assert_eq!(samples.len(), CLIP_SECS * SAMPLE_RATE);
+            assert_eq!(targets.len(), 1);
+            std::fs::write(&targets[0].path, "test").unwrap();
+            output::write_checksum(&targets[0].path).unwrap();
+            written = targets[0].path.parent().map(Path::to_path_buf);
+            Ok(Metrics {
+                audio_duration_secs: CLIP_SECS as f64,
+                transcribe_secs: 3.0,
+                realtime_factor: 0.3,
+                segments: 0,
+                stages: StageTimings {
+                    transcribe_secs: 3.0,
+                    ..Default::default()
+                },
+            })
+        })
+        .unwrap();
+
+        let dir = written.expect("pipeline called");
+        assert!(dir.starts_with(std::env::temp_dir()));
+        assert!(!dir.exists(), "transcript and checksum removed");
+        assert_eq!(metrics.realtime_factor, 0.3);
+        let report = report("tiny", &metrics);
+        assert!(report.contains("Realtime factor: 0.30x (3.3x faster than realtime)"));
+        assert!(report.contains("Transcribe:       3.00s"));
+    }
+
+    #[test]
+    fn temp_dirs_are_fresh() {
+        let (a, b) = (TempDir::create().unwrap(), TempDir::create().unwrap());
+        assert_ne!(a.0, b.0);
+        assert_eq!(std::fs::read_dir(&a.0).unwrap().count(), 0);
+        let path = a.0.clone();
+        drop(a);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,158 @@
+//! Turkish sentence splitting for `--format lines`.
+//!
+//! A sentence ends at `.`, `?`, `!` or `…`, except after titles (`Dr.`,
+//! `Prof.`), ordinals and list markers (`3.`), and initials (`A.`). List
+//! abbreviations like `vs.` and `vb.` end a sentence only when the next
+//! word starts with a capital letter.
+
+use crate::postprocess::turkish_lowercase;
+
+/// Abbreviations that are always followed by more of the same sentence.
+static TITLES: &[&str] = &[
+    "dr", "prof", "doç", "yrd", "uzm", "op", "av", "sn", "müh", "mim", "alb", "gen", "bkz", "örn",
+    "no", "tel", "mah", "cad", "sok", "apt", "st",
+];
+
+/// Abbreviations that may also close a sentence (`elma, armut vs.`).
+static LIST_ABBREVIATIONS: &[&str] = &["vs", "vb", "vd", "ltd", "şti"];
+
+/// Closing quotes and brackets allowed after the final punctuation.
+const CLOSERS: &[char] = &['"', '\'', ')', ']', '»', '”', '’'];
+
+/// Split `text` into sentences, trimmed, one per element.
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let mut sentences = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        current.push(token);
+        if ends_sentence(token, tokens.get(i + 1).copied()) {
+            sentences.push(current.join(" "));
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        sentences.push(current.join(" "));
+    }
+    sentences
+}
+
+/// Whether `token` closes a sentence, given the word that follows it.
+fn ends_sentence(token: &str, next: Option<&str>) -> bool {
+    let bare = token.trim_end_matches(CLOSERS);
+    if bare.ends_with(['?', '!', '…']) {
+        return true;
+    }
+    if !bare.ends_with('.') {
+        return false;
+    }
+    // An ellipsis written as dots behaves like `…`
+    if bare.ends_with("..") {
+        return true;
+    }
+
+    let core = bare
+        .trim_end_matches('.')
+        .trim_start_matches(|c: char| !c.is_alphanumeric());
+    let next_is_capital = next
+        .and_then(|w| {
+            w.trim_start_matches(|c: char| !c.is_alphanumeric())
+                .chars()
+                .next()
+        })
+        .is_some_and(char::is_uppercase);
+
+    let word = turkish_lowercase(core);
+    if TITLES.contains(&word.as_str()) {
+        return false;
+    }
+    // Ordinals and list markers: "3. sınıf", "1. madde"
+    if !core.is_empty() && core.chars().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+    // Initials: "A. Yılmaz"; a lone "O." at the end is the pronoun
+    if core.chars().count() == 1 && core.chars().all(char::is_uppercase) && next_is_capital {
+        return false;
+    }
+    // "vs." and dotted abbreviations like "A.Ş." only end a sentence
+    // before a capitalized word
+    if LIST_ABBREVIATIONS.contains(&word.as_str()) || core.contains('.') {
+        return next.is_none() || next_is_capital;
+    }
+    true
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::postprocess::{process, Config};
+
+    #[test]
+    fn splits_at_sentence_punctuation() {
+        assert_eq!(
+            split_sentences("Toplantı başladı. Gündem uzun mu? Evet! Sonra görüşürüz"),
+            vec![
+                "Toplantı başladı.",
+                "Gündem uzun mu?",
+                "Evet!",
+                "Sonra görüşürüz"
+            ]
+        );
+        assert_eq!(
+            split_sentences("Bekledik... Kimse gelmedi (hiç)."),
+            vec!["Bekledik...", "Kimse gelmedi (hiç)."]
+        );
+    }
+
+    #[test]
+    fn titles_and_list_markers_do_not_split() {
+        assert_eq!(
+            split_sentences("Dr. Ayşe Kaya ve Prof. Mehmet Bey geldi. 3. madde kabul edildi."),
+            vec![
+                "Dr. Ayşe Kaya ve Prof. Mehmet Bey geldi.",
+                "3. madde kabul edildi."
+            ]
+        );
+        assert_eq!(
+            split_sentences("A. Yılmaz imzaladı."),
+            vec!["A. Yılmaz imzaladı."]
+        );
+    }
+
+    #[test]
+    fn lone_pronoun_ends_a_sentence() {
+        assert_eq!(
+            split_sentences("Bunu kim yaptı? O."),
+            vec!["Bunu kim yaptı?", "O."]
+        );
+    }
+
+    #[test]
+    fn list_abbreviation_splits_only_before_capital() {
+        assert_eq!(
+            split_sentences("Elma, armut vs. aldık. Kalem, defter vs. Sonra eve döndük."),
+            vec![
+                "Elma, armut vs. aldık.",
+                "Kalem, defter vs.",
+                "Sonra eve döndük."
+            ]
+        );
+    }
+
+    #[test]
+    fn question_mark_from_particle_pass_is_a_boundary() {
+        let config = Config::default();
+        let text = [
+            process("Yarın toplantıya geliyor musun", &config),
+            process("Saat üçte başlıyor.", &config),
+        ]
+        .join(" ");
+        assert_eq!(
+            split_sentences(&text),
+            vec!["Yarın toplantıya geliyor musun?", "Saat üçte başlıyor."]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! SubRip (`.srt`) and WebVTT (`.vtt`) subtitle writers.
+//!
+//! Each transcribed segment becomes one cue. Cue text is word-wrapped to
+//! a maximum line width so players don't render one very long line; a
+//! segment too long for one cue is split into several.
+
+use std::fmt::Write as _;
+
+use crate::transcribe::Segment;
+
+/// Common subtitle line width (characters per line).
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 42;
+
+/// Preferred number of lines per cue. A third line is allowed only when
+/// the text cannot fit in two; longer text is split across cues.
+const MAX_CUE_LINES: usize = 2;
+
+/// One subtitle cue: its time span and wrapped lines.
+struct Cue {
+    start: f64,
+    end: f64,
+    lines: Vec<String>,
+}
+
+/// Render segments as an SRT document.
+pub fn render_srt(segments: &[Segment], max_line_length: usize) -> String {
+    let mut out = String::new();
+    let cues = segments
+        .iter()
+        .flat_map(|seg| split_cues(seg, max_line_length));
+    for (i, cue) in cues.enumerate() {
+        let _ = writeln!(out, "{}", i + 1);
+        let _ = writeln!(
+            out,
+            "{} --> {}",
+            format_timestamp(cue.start, ','),
+            format_timestamp(cue.end, ',')
+        );
+        for line in cue.lines {
+            let _ = writeln!(out, "{line}");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Render segments as a WebVTT document.
+pub fn render_vtt(segments: &[Segment], max_line_length: usize) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for cue in segments
+        .iter()
+        .flat_map(|seg| split_cues(seg, max_line_length))
+    {
+        let _ = writeln!(
+            out,
+            "{} --> {}",
+            format_timestamp(cue.start, '.'),
+            format_timestamp(cue.end, '.')
+        );
+        for line in cue.lines {
+            let _ = writeln!(out, "{line}");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Format seconds as `HH:MM:SS<sep>mmm` (SRT uses `,`, WebVTT uses `.`).
+fn format_timestamp(secs: f64, sep: char) -> String {
+    let total_ms = (secs.max(0.0) * 1000.0).round() as u64;
+    let (h, rem) = (total_ms / 3_600_000, total_ms % 3_600_000);
+    let (m, rem) = (rem / 60_000, rem % 60_000);
+    let (s, ms) = (rem / 1000, rem % 1000);
+    format!("{h:02}:{m:02}:{s:02}{sep}{ms:03}")
+}
+
+/// Wrap `seg` into cues. Text that fits in three lines stays one cue;
+/// longer text becomes several two-line cues, with the segment's time
+/// shared out by how many characters each one shows.
+fn split_cues(seg: &Segment, max: usize) -> Vec<Cue> {
+    let lines = wrap_cue(&seg.text, max);
+    if lines.len() <= MAX_CUE_LINES + 1 {
+        return vec![Cue {
+            start: seg.start,
+            end: seg.end,
+            lines,
+        }];
+    }
+
+    let width = |lines: &[String]| lines.iter().map(|l| l.chars().count()).sum::<usize>();
+    let total = width(&lines) as f64;
+    let mut shown = 0;
+    let mut start = seg.start;
+    lines
+        .chunks(MAX_CUE_LINES)
+        .map(|chunk| {
+            shown += width(chunk);
+            let end = seg.start + (seg.end - seg.start) * shown as f64 / total;
+            let cue = Cue {
+                start,
+                end,
+                lines: chunk.to_vec(),
+            };
+            start = end;
+            cue
+        })
+        .collect()
+}
+
+/// Word-wrap cue text into lines of at most `max` characters.
+///
+/// Width is measured in Unicode scalar values so Turkish letters like
+/// `ğ`/`ş`/`ı` count as one character each. Lines only break at
+/// whitespace; a single word longer than `max` gets a line of its own.
+pub fn wrap_cue(text: &str, max: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0usize;
+
+    for word in text.split_whitespace() {
+        let word_len = word.chars().count();
+        if current.is_empty() {
+            current.push_str(word);
+            current_len = word_len;
+        } else if current_len + 1 + word_len <= max {
+            current.push(' ');
+            current.push_str(word);
+            current_len += 1 + word_len;
+        } else {
+            lines.push(std::mem::take(&mut current));
+            current.push_str(word);
+            current_len = word_len;
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_cue_is_not_wrapped() {
+        assert_eq!(wrap_cue("Merhaba dünya", 42), vec!["Merhaba dünya"]);
+    }
+
+    #[test]
+    fn long_cue_wraps_at_whitespace() {
+        let text = "Bugün toplantıda kurum kültürü ve değişim yönetimi hakkında konuşacağız";
+        let lines = wrap_cue(text, 42);
+        assert_eq!(
+            lines,
+            vec![
+                "Bugün toplantıda kurum kültürü ve değişim",
+                "yönetimi hakkında konuşacağız",
+            ]
+        );
+        assert!(lines.iter().all(|l| l.chars().count() <= 42));
+    }
+
+    #[test]
+    fn width_counts_chars_not_bytes() {
+        // 10 chars but 16 bytes — must fit on one 10-wide line
+        let text = "ğış ığşçöü";
+        assert_eq!(text.chars().count(), 10);
+        assert!(text.len() > 10);
+        assert_eq!(wrap_cue(text, 10), vec!["ğış ığşçöü"]);
+        assert_eq!(wrap_cue(text, 9), vec!["ğış", "ığşçöü"]);
+    }
+
+    fn segment(start: f64, end: f64, text: &str) -> Segment {
+        Segment {
+            start,
+            end,
+            text: text.to_string(),
+            ..Segment::default()
+        }
+    }
+
+    #[test]
+    fn overflow_uses_third_line_without_truncating() {
+        let cues = split_cues(&segment(0.0, 3.0, "aaaa bbbb cccc"), 4);
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].lines, vec!["aaaa", "bbbb", "cccc"]);
+    }
+
+    #[test]
+    fn long_text_split_into_timed_cues() {
+        let cues = split_cues(&segment(10.0, 18.0, "aaaa bbbb cccc dddd eeee ffff gg"), 4);
+        let lines: Vec<_> = cues.iter().map(|c| c.lines.join("|")).collect();
+        assert_eq!(lines, vec!["aaaa|bbbb", "cccc|dddd", "eeee|ffff", "gg"]);
+        assert!(cues
+            .iter()
+            .flat_map(|c| &c.lines)
+            .all(|l| l.chars().count() <= 4));
+
+        // Time is shared by characters shown: 8 + 8 + 8 + 2 of 26
+        assert_eq!(cues[0].start, 10.0);
+        assert!((cues[0].end - (10.0 + 8.0 * 8.0 / 26.0)).abs() < 1e-9);
+        assert!(cues.windows(2).all(|w| w[0].end == w[1].start));
+        assert_eq!(cues[3].end, 18.0);
+    }
+
+    #[test]
+    fn srt_numbers_split_cues_consecutively() {
+        let segments = [
+            segment(0.0, 4.0, "aaaa bbbb cccc dddd eeee"),
+            segment(4.0, 5.0, "ffff"),
+        ];
+        let srt = render_srt(&segments, 4);
+        let numbers: Vec<_> = srt
+            .split("\n\n")
+            .filter_map(|cue| cue.lines().next())
+            .collect();
+        assert_eq!(numbers, vec!["1", "2", "3", "4"]);
+    }
+
+    #[test]
+    fn timestamp_formats() {
+        assert_eq!(format_timestamp(3723.456, ','), "01:02:03,456");
+        assert_eq!(format_timestamp(0.0, '.'), "00:00:00.000");
+    }
+}
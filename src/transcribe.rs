@@ -1,204 +1,513 @@
-use std::path::Path;
+use std::fmt::Write;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use anyhow::Result;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{ProgressBar, ProgressState, ProgressStyle};
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info, info_span, warn};
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
 use crate::audio;
-use crate::errors::{ModelError, OutputError, TranscriptionError};
+use crate::checkpoint;
+use crate::chunking;
+use crate::console::status;
+use crate::errors::{ModelError, TranscriptionError};
+use crate::language;
 use crate::model;
+use crate::output;
+use crate::postprocess;
+use crate::profile;
+use crate::segments;
+use crate::vad;
 
 /// A single transcribed segment with timestamps (in seconds).
-struct Segment {
-    start: f64,
-    end: f64,
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct Segment {
+    pub(crate) start: f64,
+    pub(crate) end: f64,
     pub(crate) text: String,
+    /// Average probability of the segment's text tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) confidence: Option<f32>,
+    /// Language the segment was transcribed with (`--multilingual` only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) language: Option<String>,
+    /// Text tokens with their probabilities, for `--format review` and
+    /// checkpoints; never written to JSON output
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tokens: Vec<TokenProb>,
 }
 
-/// Run the full transcription pipeline and write the output file.
+/// One text token of a segment and its probability.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct TokenProb {
+    pub(crate) text: String,
+    pub(crate) p: f32,
+}
+
+/// Output options forwarded from the CLI.
+pub struct Options {
+    /// Container format to assume instead of the file extension
+    pub input_format: Option<String>,
+    /// Whisper's native segment length cap; forces token timestamps
+    pub segment_max_chars: Option<usize>,
+    /// Maximum characters per subtitle line (SRT/VTT only)
+    pub max_line_length: usize,
+    /// Default behaviour when the output file already exists
+    pub write_mode: output::WriteMode,
+    /// Plain-text layout options
+    pub text: output::TextOptions,
+    /// Byte encoding of output files (`--output-encoding`)
+    pub encoding: output::Encoding,
+    /// How the source path is shown in headers and JSON
+    pub path_style: output::PathStyle,
+    /// Input root that `PathStyle::Relative` paths are relative to
+    pub path_base: PathBuf,
+    /// Write a `<output>.sha256` sidecar for each output file
+    pub write_checksum: bool,
+    /// Settings for the optional post-processing passes
+    pub postprocess: postprocess::Config,
+    /// Drop or flag segments with low average token probability
+    pub confidence: segments::ConfidenceFilter,
+    /// Drop segments whose text compresses better than this ratio
+    pub max_compression_ratio: Option<f32>,
+    /// Suppress blank output at the start of a window (Whisper default: on)
+    pub suppress_blank: bool,
+    /// Suppress non-speech tokens like `[music]` (Whisper default: off)
+    pub suppress_nst: bool,
+    /// Decoding values given on the command line; the rest come from the
+    /// model's profile
+    pub decoding: profile::Overrides,
+    /// Languages the audio may be in, most likely first (`--language-hint`);
+    /// empty to always transcribe as Turkish
+    pub language_hint: Vec<&'static str>,
+    /// Clamp segment times to their first and last text token
+    /// (`--tighten-timestamps`)
+    pub tighten_timestamps: bool,
+    /// HTTP timeouts for downloading the model
+    pub download: model::DownloadOptions,
+    /// Save finished chunks to a `.partial` sidecar and resume from it
+    /// after a crash (`--checkpoint`)
+    pub checkpoint: bool,
+    /// Write the audio exactly as passed to Whisper to this WAV file
+    /// (`--dump-audio`)
+    pub dump_audio: Option<PathBuf>,
+    /// Retry once with relaxed parameters if the first pass finds no speech
+    pub retry: bool,
+    /// Show the inference progress bar and ETA (off under `--quiet`)
+    pub progress: bool,
+    /// Transcribe only the regions detected as speech (`--vad`)
+    pub vad: Option<vad::VadConfig>,
+    /// Detect the language per chunk and transcribe each with its own
+    /// language (experimental)
+    pub multilingual: bool,
+}
+
+/// RMS level above which audio is considered to contain real signal
+/// (about -40 dBFS).
+const SIGNAL_RMS_THRESHOLD: f32 = 0.01;
+
+/// Language used for single-language runs, and for `--multilingual`
+/// chunks until a language is detected with confidence.
+pub(crate) const DEFAULT_LANGUAGE: &str = "tr";
+
+/// Minimum detection probability for a chunk to switch language in
+/// `--multilingual` mode.
+const MIN_CHUNK_LANGUAGE_PROB: f32 = 0.5;
+
+/// Decoding parameters for one inference pass.
+#[derive(Clone, Debug)]
+struct DecodeSettings {
+    /// Beam width; `None` selects greedy sampling
+    beam_size: Option<i32>,
+    /// No-speech probability above which a window is treated as silence
+    no_speech_thold: f32,
+    /// Temperature step for Whisper's fallback on badly decoded windows
+    temperature_inc: f32,
+    /// Whisper's native segment length cap in characters
+    /// (`--segment-max-chars`)
+    max_chars: Option<usize>,
+    /// Whisper's `suppress_blank` (`--suppress-blank`)
+    suppress_blank: bool,
+    /// Whisper's non-speech token suppression (`--suppress-nst`)
+    suppress_nst: bool,
+    /// Clamp segments to their token timestamps (`--tighten-timestamps`)
+    tighten: bool,
+    /// Keep per-token probabilities on segments (`--format review`)
+    keep_tokens: bool,
+    /// Text the audio follows on from (`--checkpoint` chunks after the first)
+    prompt: Option<String>,
+}
+
+impl DecodeSettings {
+    /// Greedy sampling with a lenient no-speech threshold (fallback pass).
+    const RELAXED: Self = Self {
+        beam_size: None,
+        no_speech_thold: 0.3,
+        temperature_inc: 0.2,
+        max_chars: None,
+        suppress_blank: true,
+        suppress_nst: false,
+        tighten: false,
+        keep_tokens: false,
+        prompt: None,
+    };
+
+    /// Beam search with the model profile's settings (first pass).
+    fn first_pass(profile: &profile::Profile) -> Self {
+        Self {
+            beam_size: Some(profile.beam_size),
+            no_speech_thold: profile.no_speech_thold,
+            temperature_inc: profile.temperature_inc,
+            ..Self::RELAXED
+        }
+    }
+
+    /// These settings with a segment length cap.
+    fn with_max_chars(&self, max_chars: Option<usize>) -> Self {
+        Self {
+            max_chars,
+            ..self.clone()
+        }
+    }
+
+    /// These settings with the given token suppression.
+    fn with_suppression(&self, suppress_blank: bool, suppress_nst: bool) -> Self {
+        Self {
+            suppress_blank,
+            suppress_nst,
+            ..self.clone()
+        }
+    }
+
+    /// These settings with token-timestamp tightening on or off.
+    fn with_tightening(&self, tighten: bool) -> Self {
+        Self {
+            tighten,
+            ..self.clone()
+        }
+    }
+
+    /// These settings with per-token probabilities kept or not.
+    fn with_tokens(&self, keep_tokens: bool) -> Self {
+        Self {
+            keep_tokens,
+            ..self.clone()
+        }
+    }
+
+    /// These settings with `prompt` as the preceding text.
+    fn with_prompt(&self, prompt: Option<String>) -> Self {
+        Self {
+            prompt,
+            ..self.clone()
+        }
+    }
+
+    fn params(&self, language: &'static str) -> FullParams<'static, 'static> {
+        let strategy = match self.beam_size {
+            Some(beam_size) => SamplingStrategy::BeamSearch {
+                beam_size,
+                patience: -1.0,
+            },
+            None => SamplingStrategy::Greedy { best_of: 1 },
+        };
+
+        let mut params = FullParams::new(strategy);
+        params.set_language(Some(language));
+        params.set_translate(false);
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        params.set_no_speech_thold(self.no_speech_thold);
+        params.set_temperature_inc(self.temperature_inc);
+        params.set_token_timestamps(self.token_timestamps());
+        if let Some(max_len) = self.max_len() {
+            // Splitting on word boundaries keeps words whole
+            params.set_max_len(max_len);
+            params.set_split_on_word(true);
+        }
+        params.set_suppress_blank(self.suppress_blank);
+        params.set_suppress_nst(self.suppress_nst);
+        if let Some(prompt) = &self.prompt {
+            params.set_initial_prompt(prompt);
+        }
+        params
+    }
+
+    /// Whisper's `max_len` for the segment length cap, if any.
+    fn max_len(&self) -> Option<i32> {
+        self.max_chars.map(|n| n.min(i32::MAX as usize) as i32)
+    }
+
+    /// Whether token-level timestamps are needed: for tightening, and
+    /// because Whisper's `max_len` only takes effect with them.
+    fn token_timestamps(&self) -> bool {
+        self.tighten || self.max_chars.is_some()
+    }
+}
+
+/// Segments collected from one inference pass.
+struct Pass {
+    segments: Vec<Segment>,
+    skipped: u32,
+    total_chars: usize,
+}
+
+/// Performance figures for one transcribed file.
+#[derive(Clone, Debug, Serialize)]
+pub struct Metrics {
+    /// Length of the decoded audio in seconds
+    pub audio_duration_secs: f64,
+    /// Wall-clock inference time in seconds
+    pub transcribe_secs: f64,
+    /// Inference time divided by audio length
+    pub realtime_factor: f64,
+    /// Segments written to the output
+    pub segments: usize,
+    /// Wall-clock time of each pipeline stage
+    pub stages: StageTimings,
+}
+
+/// Seconds spent in each pipeline stage.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct StageTimings {
+    pub resolve_model_secs: f64,
+    pub decode_secs: f64,
+    pub load_model_secs: f64,
+    pub transcribe_secs: f64,
+    pub postprocess_secs: f64,
+    pub write_secs: f64,
+}
+
+/// Run the full transcription pipeline and write one file per target.
+/// Inference runs once; only the writers differ between targets.
 #[tracing::instrument(skip_all, fields(
     audio = %audio_path.display(),
     model = model_size,
-    output = %output_path.display(),
+    outputs = targets.len(),
 ))]
-pub fn run(audio_path: &Path, model_size: &str, output_path: &Path) -> Result<()> {
-    let pipeline_start = Instant::now();
+pub fn run(
+    audio_path: &Path,
+    model_size: &str,
+    targets: &[output::Target],
+    write_mode: output::WriteMode,
+    opts: &Options,
+) -> Result<Metrics> {
+    pipeline(audio_path, model_size, targets, write_mode, opts, |p| {
+        audio::load_audio(p, opts.input_format.as_deref())
+    })
+}
 
-    // ── Resolve model ────────────────────────────────────────────────
-    let (model_path, is_bundled) = {
-        let _span = info_span!("resolve_model").entered();
-        model::resolve_model(model_size)?
+/// Run the full pipeline on samples already in memory (16 kHz mono).
+/// `label` stands in for the source file name in logs and headers.
+pub fn run_samples(
+    label: &Path,
+    samples: Vec<f32>,
+    model_size: &str,
+    targets: &[output::Target],
+    opts: &Options,
+) -> Result<Metrics> {
+    let info = audio::AudioInfo {
+        codec: "pcm".to_string(),
+        sample_rate: 16_000,
+        channels: 1,
     };
-    let label = if is_bundled { "bundled" } else { "cached/downloaded" };
-    info!(
-        model = %model_path.file_name().unwrap_or_default().to_string_lossy(),
-        source = label,
-        "Model resolved"
-    );
-    info!(
-        input = %audio_path.file_name().unwrap_or_default().to_string_lossy(),
-        "Input file"
-    );
-    eprintln!(
-        "[1/5] Model: whisper-{model_size} ({label})"
-    );
+    pipeline(
+        label,
+        model_size,
+        targets,
+        output::WriteMode::Overwrite,
+        opts,
+        |_| Ok((samples, info)),
+    )
+}
 
-    // ── Load audio ───────────────────────────────────────────────────
-    let samples = {
-        let _span = info_span!("load_audio").entered();
-        let file_name = audio_path.file_name().unwrap_or_default().to_string_lossy();
-        eprintln!("[2/5] Decoding audio: {file_name}");
-        let t0 = Instant::now();
-        let s = audio::load_audio(audio_path)?;
-        let secs = t0.elapsed().as_secs_f64();
-        info!(elapsed_secs = format!("{secs:.1}"), "Audio loaded");
-        eprintln!("       Decoded in {secs:.1}s");
-        s
-    };
+/// Pipeline shared by [`run`] and [`run_samples`]; `load` supplies the
+/// decoded audio.
+fn pipeline(
+    audio_path: &Path,
+    model_size: &str,
+    targets: &[output::Target],
+    write_mode: output::WriteMode,
+    opts: &Options,
+    load: impl FnOnce(&Path) -> Result<(Vec<f32>, audio::AudioInfo)>,
+) -> Result<Metrics> {
+    let pipeline_start = Instant::now();
+
+    let Prepared {
+        model_path,
+        samples,
+        mut stages,
+        ..
+    } = prepare(
+        audio_path,
+        model_size,
+        |size| model::resolve_model(size, &opts.download),
+        load,
+    )?;
 
     let audio_duration_secs = samples.len() as f64 / 16_000.0;
     let audio_mins = audio_duration_secs / 60.0;
-    eprintln!("       Audio length: {audio_mins:.1} minutes");
+    status!("       Audio length: {audio_mins:.1} minutes");
+
+    // ── Voice activity detection ─────────────────────────────────────
+    let vad_map = opts.vad.as_ref().map(|config| {
+        let _span = info_span!("vad").entered();
+        let regions = vad::speech_regions(&samples, config);
+        let map = vad::TimeMap::new(&regions);
+        let kept = map.kept_secs();
+        info!(
+            regions = regions.len(),
+            kept_secs = format!("{kept:.1}"),
+            skipped_secs = format!("{:.1}", audio_duration_secs - kept),
+            "Voice activity detection complete"
+        );
+        status!(
+            "       VAD: {kept:.1}s of speech in {} regions, skipping {:.1}s",
+            regions.len(),
+            audio_duration_secs - kept
+        );
+        map
+    });
+    // Only the speech regions are transcribed; timestamps are mapped back
+    // onto the original timeline afterwards
+    let samples = match &vad_map {
+        Some(map) => map.gather(&samples),
+        None => samples,
+    };
+    let inference_secs = samples.len() as f64 / 16_000.0;
+
+    if let Some(path) = &opts.dump_audio {
+        audio::write_wav(path, &samples, write_mode)?;
+        info!(path = %path.display(), samples = samples.len(), "Dumped Whisper input audio");
+        status!("       Audio sent to Whisper saved to {}", path.display());
+    }
+
+    // ── Checkpoint ───────────────────────────────────────────────────
+    let keep_tokens = targets.iter().any(|t| t.format == "review");
+    let configure = |settings: &DecodeSettings| {
+        settings
+            .with_max_chars(opts.segment_max_chars)
+            .with_suppression(opts.suppress_blank, opts.suppress_nst)
+            .with_tightening(opts.tighten_timestamps)
+            .with_tokens(keep_tokens)
+    };
+    let profile = profile::resolve(model_size, &opts.decoding);
+    let first_pass = configure(&DecodeSettings::first_pass(&profile));
+
+    let checkpoint_path = checkpoint::sidecar_path(targets, audio_path);
+    let saved = if opts.checkpoint {
+        checkpoint::start(
+            &checkpoint_path,
+            audio_path,
+            model_size,
+            &checkpoint_key(&first_pass, opts),
+            samples.len(),
+            chunking::CHUNK_SECS * 16_000,
+        )
+    } else {
+        None
+    };
+    let checkpointing = saved.is_some();
 
     // ── Load Whisper model ───────────────────────────────────────────
     let ctx = {
         let _span = info_span!("load_whisper").entered();
-        eprintln!("[3/5] Loading whisper-{model_size} model...");
+        status!("[3/5] Loading whisper-{model_size} model...");
         let t0 = Instant::now();
-        let model_str = model_path
-            .to_str()
-            .ok_or_else(|| ModelError::InvalidPath(model_path.display().to_string()))?;
-        // GPU is auto-enabled when compiled with vulkan/cuda feature
-        let c = WhisperContext::new_with_params(model_str, WhisperContextParameters::default())
-            .map_err(|e| ModelError::LoadFailed(e.to_string()))?;
-        let secs = t0.elapsed().as_secs_f64();
-        info!(elapsed_secs = format!("{secs:.1}"), "Whisper model loaded");
-        eprintln!("       Model loaded in {secs:.1}s");
-        c
+        let ctx = load_context(&model_path)?;
+        stages.load_model_secs = t0.elapsed().as_secs_f64();
+        ctx
     };
 
     // ── Transcribe ───────────────────────────────────────────────────
-    let (mut segments, transcribe_secs) = {
+    let (mut segments, realtime_factor, transcribe_secs) = {
         let _span = info_span!("transcribe").entered();
         info!("Transcribing...");
-        eprintln!("[4/5] Transcribing ({audio_mins:.1} min of audio)...");
+        status!("[4/5] Transcribing ({audio_mins:.1} min of audio)...");
 
-        let pb = ProgressBar::new(100);
+        let pb = if opts.progress {
+            ProgressBar::new(100)
+        } else {
+            ProgressBar::hidden()
+        };
+        let typical_rtf = model::typical_realtime_factor(model_size);
         pb.set_style(
             ProgressStyle::with_template(
                 "       [{bar:40.green/dim}] {pos}% | elapsed: {elapsed_precise} | ETA: {eta}",
             )
             .unwrap()
+            .with_key("eta", move |state: &ProgressState, w: &mut dyn Write| {
+                let secs = eta_secs(
+                    state.elapsed().as_secs_f64(),
+                    state.pos(),
+                    inference_secs,
+                    typical_rtf,
+                );
+                let _ = write!(w, "{}", format_eta(secs));
+            })
             .progress_chars("=> "),
         );
         pb.set_position(0);
 
         let t0 = Instant::now();
 
-        let mut state = ctx
-            .create_state()
-            .map_err(|e| TranscriptionError::StateCreation(e.to_string()))?;
+        let hinted = !opts.language_hint.is_empty() && !opts.multilingual;
+        let language = if hinted && !samples.is_empty() {
+            hinted_language(&ctx, &samples, &opts.language_hint)?
+        } else {
+            DEFAULT_LANGUAGE
+        };
 
-        let mut params = FullParams::new(SamplingStrategy::BeamSearch {
-            beam_size: 5,
-            patience: -1.0,
-        });
-        params.set_language(Some("tr"));
-        params.set_translate(false);
-        params.set_print_special(false);
-        params.set_print_progress(false);
-        params.set_print_realtime(false);
-        params.set_print_timestamps(false);
-        params.set_no_speech_thold(0.6);
-
-        let threads = std::thread::available_parallelism()
-            .map(|n| n.get() as i32)
-            .unwrap_or(4);
-        params.set_n_threads(threads);
-        debug!(threads, "Inference threads");
-
-        // Progress callback — drives the progress bar
-        let pb_cb = pb.clone();
-        params.set_progress_callback_safe(move |progress: i32| {
-            pb_cb.set_position(progress.max(0) as u64);
-        });
-
-        // Segment callback — show live segments as they arrive
-        let seg_count = Arc::new(Mutex::new(0u32));
-        let seg_count_cb = Arc::clone(&seg_count);
-        let pb_seg = pb.clone();
-        params.set_segment_callback_safe_lossy(move |data: whisper_rs::SegmentCallbackData| {
-            let mut count = seg_count_cb.lock().unwrap();
-            *count += 1;
-            let text = data.text.trim();
-            if !text.is_empty() {
-                let preview: String = text.chars().take(60).collect();
-                pb_seg.set_message(format!("[seg {count}] {preview}"));
+        let run_pass = |settings: &DecodeSettings, saved: Option<checkpoint::Checkpoint>| {
+            if samples.is_empty() {
+                return Ok(Pass {
+                    segments: Vec::new(),
+                    skipped: 0,
+                    total_chars: 0,
+                });
+            }
+            if let Some(saved) = saved {
+                let path = &checkpoint_path;
+                infer_checkpointed(&ctx, &samples, settings, language, &pb, saved, path)
+            } else if opts.multilingual {
+                infer_multilingual(&ctx, &samples, settings, &opts.language_hint, &pb)
+            } else {
+                infer(&ctx, &samples, settings, language, &pb, 0..100)
             }
-        });
+        };
 
-        state
-            .full(params, &samples)
-            .map_err(|e| TranscriptionError::InferenceFailed(e.to_string()))?;
+        let mut pass = run_pass(&first_pass, saved)?;
+        if should_retry(pass.segments.len(), audio::rms(&samples), opts.retry) {
+            warn!("No segments despite audible signal — retrying with relaxed parameters");
+            status!("       No speech found, retrying with relaxed settings...");
+            pb.set_position(0);
+            pb.reset_elapsed();
+            pass = run_pass(&configure(&DecodeSettings::RELAXED), None)?;
+        }
+        if let Some(map) = &vad_map {
+            map.remap(&mut pass.segments);
+        }
+        // Keep Turkish-specific post-processing off other languages
+        if language != DEFAULT_LANGUAGE {
+            for seg in &mut pass.segments {
+                seg.language = Some(language.to_string());
+            }
+        }
 
         pb.finish_and_clear();
         let elapsed = t0.elapsed().as_secs_f64();
 
-        // ── Collect segments ─────────────────────────────────────────
-        let n = state.full_n_segments();
-
-        let mut segments: Vec<Segment> = Vec::with_capacity(n as usize);
-        let mut skipped = 0u32;
-        let mut total_chars: usize = 0;
-
-        for i in 0..n {
-            let seg = match state.get_segment(i) {
-                Some(s) => s,
-                None => {
-                    skipped += 1;
-                    continue;
-                }
-            };
-
-            let t0 = seg.start_timestamp();
-            let t1 = seg.end_timestamp();
-
-            // Validate timestamps
-            if t0 < 0 || t1 < 0 {
-                warn!(segment = i, start = t0, end = t1, "Negative timestamp — skipping segment");
-                skipped += 1;
-                continue;
-            }
-            if t1 < t0 {
-                warn!(segment = i, start = t0, end = t1, "Inverted timestamps — skipping segment");
-                skipped += 1;
-                continue;
-            }
-
-            let text = match seg.to_str_lossy() {
-                Ok(t) => t,
-                Err(_) => {
-                    skipped += 1;
-                    continue;
-                }
-            };
-            let trimmed = text.trim().to_string();
-            if trimmed.is_empty() {
-                debug!(segment = i, "Empty text — skipping segment");
-                skipped += 1;
-                continue;
-            }
-
-            total_chars += trimmed.len();
-            segments.push(Segment {
-                start: t0 as f64 / 100.0,
-                end: t1 as f64 / 100.0,
-                text: trimmed,
-            });
-        }
+        let Pass {
+            segments,
+            skipped,
+            total_chars,
+        } = pass;
 
         // ── Performance metrics ──────────────────────────────────────
         let realtime_factor = if audio_duration_secs > 0.0 {
@@ -216,95 +525,769 @@ pub fn run(audio_path: &Path, model_size: &str, output_path: &Path) -> Result<()
             total_chars,
             "Transcription complete"
         );
-        eprintln!(
+        status!(
             "       Done in {elapsed:.1}s ({realtime_factor:.2}x realtime) — {} segments, {} chars",
             segments.len(),
             total_chars,
         );
 
-        (segments, elapsed)
+        (segments, realtime_factor, elapsed)
     };
 
+    stages.transcribe_secs = transcribe_secs;
+
     // ── Post-process Turkish text ────────────────────────────────────
     {
         let _span = info_span!("postprocess").entered();
-        for seg in &mut segments {
-            seg.text = crate::postprocess::process(&seg.text);
+        let t0 = Instant::now();
+        if let Some(max_ratio) = opts.max_compression_ratio {
+            segments::drop_repetitive(&mut segments, max_ratio);
+        }
+        for seg in segments.iter_mut() {
+            seg.text = postprocess_segment(seg, &opts.postprocess);
         }
+        // Segments that were only filler sounds
+        segments.retain(|s| !s.text.is_empty());
+        segments::filter_confidence(&mut segments, &opts.confidence);
+        stages.postprocess_secs = t0.elapsed().as_secs_f64();
         info!(segments = segments.len(), "Post-processing complete");
     }
 
     // ── Write output ─────────────────────────────────────────────────
     {
         let _span = info_span!("write_output").entered();
-        write_output(output_path, audio_path, model_size, transcribe_secs, &segments)?;
-        info!(path = %output_path.display(), "Output written");
+        let t0 = Instant::now();
+        let source = output::source_path(audio_path, opts.path_style, &opts.path_base);
+        let doc = output::Document {
+            source: &source,
+            model_size,
+            duration: transcribe_secs,
+            segments: &segments,
+        };
+        output::write_outputs(
+            targets,
+            &doc,
+            &opts.text,
+            opts.max_line_length,
+            write_mode,
+            opts.encoding,
+        )?;
+        for target in targets {
+            info!(path = %target.path.display(), format = %target.format, "Output written");
+            if opts.write_checksum && target.path != Path::new(output::STDOUT_PATH) {
+                let sidecar = output::write_checksum(&target.path)?;
+                debug!(path = %sidecar.display(), "Checksum written");
+            }
+        }
+        if checkpointing {
+            checkpoint::finish(&checkpoint_path);
+        }
+        stages.write_secs = t0.elapsed().as_secs_f64();
     }
 
     let total_elapsed = pipeline_start.elapsed().as_secs_f64();
     info!(total_secs = format!("{total_elapsed:.1}"), "Pipeline complete");
-    eprintln!(
-        "[5/5] Saved to: {}",
-        output_path.display()
-    );
-    eprintln!("       Total time: {total_elapsed:.1}s");
+    for (i, target) in targets.iter().enumerate() {
+        let label = if i == 0 { "[5/5] Saved to:" } else { "               " };
+        status!("{label} {}", target.path.display());
+    }
+    status!("       Total time: {total_elapsed:.1}s");
 
-    Ok(())
+    Ok(Metrics {
+        audio_duration_secs,
+        transcribe_secs,
+        realtime_factor,
+        segments: segments.len(),
+        stages,
+    })
 }
 
-/// Write the transcript file matching the Python version's format exactly.
-#[tracing::instrument(skip_all, fields(path = %path.display()))]
-fn write_output(
-    path: &Path,
-    source: &Path,
+/// Validate inputs without transcribing: resolve the model and decode the
+/// audio, then report what was found. The Whisper context is never loaded
+/// and no output is written.
+#[tracing::instrument(skip_all, fields(audio = %audio_path.display(), model = model_size))]
+pub fn dry_run(
+    audio_path: &Path,
     model_size: &str,
-    duration: f64,
-    segments: &[Segment],
+    input_format: Option<&str>,
+    download: &model::DownloadOptions,
 ) -> Result<()> {
-    use std::io::Write;
+    let prepared = prepare(
+        audio_path,
+        model_size,
+        |size| model::resolve_model(size, download),
+        |p| audio::load_audio(p, input_format),
+    )?;
+    print!("{}", prepared.summary());
+    Ok(())
+}
+
+/// Model and audio resolved ahead of inference.
+struct Prepared {
+    model_path: PathBuf,
+    is_bundled: bool,
+    samples: Vec<f32>,
+    info: audio::AudioInfo,
+    /// Resolve and decode timings; later stages are filled in by `run`
+    stages: StageTimings,
+}
+
+impl Prepared {
+    /// Human-readable report used by `--dry-run`.
+    fn summary(&self) -> String {
+        let source = if self.is_bundled { "bundled" } else { "cached/downloaded" };
+        format!(
+            "Model: {} ({source})\nFormat: {}, {} Hz, {} ch\nDuration: {:.1}s ({} samples at 16 kHz)\n",
+            self.model_path.display(),
+            self.info.codec,
+            self.info.sample_rate,
+            self.info.channels,
+            self.samples.len() as f64 / 16_000.0,
+            self.samples.len(),
+        )
+    }
+}
+
+/// Resolve the model and decode the audio (pipeline stages 1 and 2).
+/// Both steps are passed in so the control flow can be tested without
+/// a real model or audio file.
+fn prepare(
+    audio_path: &Path,
+    model_size: &str,
+    resolve: impl FnOnce(&str) -> Result<(PathBuf, bool)>,
+    load: impl FnOnce(&Path) -> Result<(Vec<f32>, audio::AudioInfo)>,
+) -> Result<Prepared> {
+    let mut stages = StageTimings::default();
 
-    let mut f = std::fs::File::create(path).map_err(|e| OutputError::FileCreate {
-        path: path.display().to_string(),
-        source: e,
-    })?;
+    // ── Resolve model ────────────────────────────────────────────────
+    let (model_path, is_bundled) = {
+        let _span = info_span!("resolve_model").entered();
+        let t0 = Instant::now();
+        let resolved = resolve(model_size)?;
+        stages.resolve_model_secs = t0.elapsed().as_secs_f64();
+        resolved
+    };
+    let label = if is_bundled { "bundled" } else { "cached/downloaded" };
+    info!(
+        model = %model_path.file_name().unwrap_or_default().to_string_lossy(),
+        source = label,
+        "Model resolved"
+    );
+    info!(
+        input = %audio_path.file_name().unwrap_or_default().to_string_lossy(),
+        "Input file"
+    );
+    status!(
+        "[1/5] Model: whisper-{model_size} ({label})"
+    );
+
+    // ── Load audio ───────────────────────────────────────────────────
+    let (samples, info) = {
+        let _span = info_span!("load_audio").entered();
+        let file_name = audio_path.file_name().unwrap_or_default().to_string_lossy();
+        status!("[2/5] Decoding audio: {file_name}");
+        let t0 = Instant::now();
+        let loaded = load(audio_path)?;
+        let secs = t0.elapsed().as_secs_f64();
+        stages.decode_secs = secs;
+        info!(elapsed_secs = format!("{secs:.1}"), "Audio loaded");
+        status!("       Decoded in {secs:.1}s");
+        loaded
+    };
+
+    Ok(Prepared {
+        model_path,
+        is_bundled,
+        samples,
+        info,
+        stages,
+    })
+}
+
+/// Run one inference pass and collect the valid segments.
+#[tracing::instrument(skip_all, fields(settings = ?settings))]
+fn infer(
+    ctx: &WhisperContext,
+    samples: &[f32],
+    settings: &DecodeSettings,
+    language: &'static str,
+    pb: &ProgressBar,
+    progress: Range<u64>,
+) -> Result<Pass> {
+    let mut state = ctx
+        .create_state()
+        .map_err(|e| TranscriptionError::StateCreation(e.to_string()))?;
+
+    let mut params = settings.params(language);
+
+    let threads = inference_threads() as i32;
+    params.set_n_threads(threads);
+    debug!(threads, "Inference threads");
+
+    // Progress callback — drives this call's share of the progress bar
+    let pb_cb = pb.clone();
+    params.set_progress_callback_safe(move |percent: i32| {
+        let done = percent.clamp(0, 100) as u64 * (progress.end - progress.start) / 100;
+        pb_cb.set_position(progress.start + done);
+    });
+
+    // Segment callback — show live segments as they arrive
+    let seg_count = Arc::new(Mutex::new(0u32));
+    let seg_count_cb = Arc::clone(&seg_count);
+    let pb_seg = pb.clone();
+    params.set_segment_callback_safe_lossy(move |data: whisper_rs::SegmentCallbackData| {
+        let mut count = seg_count_cb.lock().unwrap();
+        *count += 1;
+        let text = data.text.trim();
+        if !text.is_empty() {
+            let preview: String = text.chars().take(60).collect();
+            pb_seg.set_message(format!("[seg {count}] {preview}"));
+        }
+    });
+
+    state
+        .full(params, samples)
+        .map_err(|e| TranscriptionError::InferenceFailed(e.to_string()))?;
+
+    // ── Collect segments ─────────────────────────────────────────────
+    let n = state.full_n_segments();
+
+    let mut segments: Vec<Segment> = Vec::with_capacity(n as usize);
+    let mut skipped = 0u32;
+    let mut total_chars: usize = 0;
+    let mut untimed = 0u32;
+
+    for i in 0..n {
+        let seg = match state.get_segment(i) {
+            Some(s) => s,
+            None => {
+                skipped += 1;
+                continue;
+            }
+        };
+
+        let t0 = seg.start_timestamp();
+        let t1 = seg.end_timestamp();
 
-    macro_rules! w {
-        ($($arg:tt)*) => {
-            write!(f, $($arg)*).map_err(|e| OutputError::WriteFailed(e.to_string()))?
+        // Validate timestamps
+        if t0 < 0 || t1 < 0 {
+            warn!(segment = i, start = t0, end = t1, "Negative timestamp — skipping segment");
+            skipped += 1;
+            continue;
+        }
+        if t1 < t0 {
+            warn!(segment = i, start = t0, end = t1, "Inverted timestamps — skipping segment");
+            skipped += 1;
+            continue;
+        }
+
+        let text = match seg.to_str_lossy() {
+            Ok(t) => t,
+            Err(_) => {
+                skipped += 1;
+                continue;
+            }
         };
+        let trimmed = text.trim().to_string();
+        if trimmed.is_empty() {
+            debug!(segment = i, "Empty text — skipping segment");
+            skipped += 1;
+            continue;
+        }
+
+        total_chars += trimmed.len();
+        let mut segment = Segment {
+            start: t0 as f64 / 100.0,
+            end: t1 as f64 / 100.0,
+            text: trimmed,
+            confidence: segment_confidence(&seg, ctx.token_eot()),
+            language: None,
+            tokens: if settings.keep_tokens {
+                token_probs(&seg, ctx.token_eot())
+            } else {
+                Vec::new()
+            },
+        };
+        if settings.tighten {
+            let tokens = token_spans(&seg, ctx.token_eot());
+            if !segments::tighten(&mut segment, &tokens) {
+                untimed += 1;
+            }
+        }
+        segments.push(segment);
+    }
+    if untimed > 0 {
+        warn!(
+            segments = untimed,
+            "No token timestamps — --tighten-timestamps left these segments unchanged"
+        );
     }
 
-    if segments.is_empty() {
-        w!("No speech detected in the audio.\n");
-        return Ok(());
+    Ok(Pass {
+        segments,
+        skipped,
+        total_chars,
+    })
+}
+
+/// `--multilingual`: detect the language of each chunk, then transcribe
+/// each run of same-language chunks with that language and tag its
+/// segments. Slower than a single pass, and detection on short or noisy
+/// chunks is a heuristic. With `--language-hint`, chunks detected as a
+/// language outside `hints` use the first hint.
+fn infer_multilingual(
+    ctx: &WhisperContext,
+    samples: &[f32],
+    settings: &DecodeSettings,
+    hints: &[&'static str],
+    pb: &ProgressBar,
+) -> Result<Pass> {
+    let ranges = chunking::chunk_ranges(samples.len(), chunking::CHUNK_SECS * 16_000);
+
+    let mut state = ctx
+        .create_state()
+        .map_err(|e| TranscriptionError::StateCreation(e.to_string()))?;
+    let threads = inference_threads();
+    let mut detected = Vec::with_capacity(ranges.len());
+    for range in &ranges {
+        let probs = language_probs(&mut state, &samples[range.clone()], threads)?;
+        let top = language::top_candidates(&probs, 1);
+        let (code, p) = top.first().copied().unwrap_or((DEFAULT_LANGUAGE, 0.0));
+        detected.push((language::constrain(code, hints), p));
     }
 
-    // Header
-    w!("=== TRANSCRIPT (Turkish) ===\n");
-    w!(
-        "Source: {}\n",
-        source.file_name().unwrap_or_default().to_string_lossy()
+    let fallback = hints.first().copied().unwrap_or(DEFAULT_LANGUAGE);
+    let languages = chunking::assign_languages(&detected, MIN_CHUNK_LANGUAGE_PROB, fallback);
+    let runs = chunking::language_runs(samples, &ranges, &languages);
+    info!(
+        chunks = ranges.len(),
+        runs = ?runs.iter().map(|r| r.language).collect::<Vec<_>>(),
+        "Per-chunk language detection complete"
     );
-    w!("Model: whisper-{model_size}\n");
-    w!("Duration: {duration:.1}s\n");
-    w!("{}\n", "=".repeat(40));
-    w!("\n");
 
-    // Full text
-    let full: String = segments
-        .iter()
-        .map(|s| s.text.as_str())
-        .collect::<Vec<_>>()
-        .join(" ");
-    w!("{full}\n\n");
+    let mut merged = Pass {
+        segments: Vec::new(),
+        skipped: 0,
+        total_chars: 0,
+    };
+    for run in &runs {
+        let progress = progress_span(&run.range, samples.len());
+        let range = run.range.clone();
+        let mut pass = infer(ctx, &samples[range], settings, run.language, pb, progress)?;
+        chunking::place_segments(&mut pass.segments, run.range.start, run.language);
+        merged.segments.append(&mut pass.segments);
+        merged.skipped += pass.skipped;
+        merged.total_chars += pass.total_chars;
+    }
+    Ok(merged)
+}
+
+/// What a checkpoint's chunks depend on besides the input and model:
+/// chunks decoded with other settings are not resumed.
+fn checkpoint_key(settings: &DecodeSettings, opts: &Options) -> String {
+    format!(
+        "{settings:?} vad={:?} languages={:?}",
+        opts.vad, opts.language_hint
+    )
+}
+
+/// The part of the 0–100 progress bar covered by samples `range` out of
+/// `total`, so chunk after chunk fills the bar once.
+fn progress_span(range: &Range<usize>, total: usize) -> Range<u64> {
+    let percent = |at: usize| at as u64 * 100 / total.max(1) as u64;
+    percent(range.start)..percent(range.end)
+}
+
+/// `--checkpoint`: transcribe one chunk at a time, starting at the first
+/// chunk `saved` doesn't have, and save the sidecar after every chunk so a
+/// restarted run can pick up where this one stopped.
+fn infer_checkpointed(
+    ctx: &WhisperContext,
+    samples: &[f32],
+    settings: &DecodeSettings,
+    language: &'static str,
+    pb: &ProgressBar,
+    mut saved: checkpoint::Checkpoint,
+    path: &Path,
+) -> Result<Pass> {
+    let ranges = chunking::quiet_chunk_ranges(samples, saved.chunk_len);
+    let resume = saved.resume_point();
+    if resume > 0 {
+        status!(
+            "       Resuming from checkpoint: {resume}/{} chunks already done",
+            ranges.len()
+        );
+    }
+
+    let mut skipped = 0;
+    for (index, range) in ranges.iter().enumerate().skip(resume) {
+        // Whisper conditions each window on the text before it; give the
+        // chunk the previous one's text, as a single pass would
+        let prompt = index.checked_sub(1).and_then(|prev| saved.chunk_text(prev));
+        let settings = &settings.with_prompt(prompt);
+        let progress = progress_span(range, samples.len());
+        let chunk = &samples[range.clone()];
+        let mut pass = infer(ctx, chunk, settings, language, pb, progress)?;
+        chunking::shift_segments(&mut pass.segments, range.start);
+        skipped += pass.skipped;
+        saved.record(index, pass.segments);
+        saved.save(path)?;
+        debug!(chunk = index, total = ranges.len(), "Checkpoint saved");
+    }
+
+    let segments = saved.into_segments();
+    let total_chars = segments.iter().map(|s| s.text.len()).sum();
+    Ok(Pass {
+        segments,
+        skipped,
+        total_chars,
+    })
+}
+
+/// `--language-hint`: detect the language of the first 30 s and keep it
+/// if it is one of `hints`; otherwise use the first hint rather than
+/// trusting the detection.
+fn hinted_language(
+    ctx: &WhisperContext,
+    samples: &[f32],
+    hints: &[&'static str],
+) -> Result<&'static str> {
+    let mut state = ctx
+        .create_state()
+        .map_err(|e| TranscriptionError::StateCreation(e.to_string()))?;
+    let window = &samples[..samples.len().min(chunking::CHUNK_SECS * 16_000)];
+    let probs = language_probs(&mut state, window, inference_threads())?;
+    let top = language::top_candidates(&probs, 1);
+    let (detected, p) = top.first().copied().unwrap_or((hints[0], 0.0));
+
+    let language = language::constrain(detected, hints);
+    info!(
+        detected,
+        probability = format!("{p:.2}"),
+        language,
+        "Language chosen from --language-hint"
+    );
+    if language != detected {
+        status!("       Detected '{detected}' is not a hinted language, using '{language}'");
+    }
+    Ok(language)
+}
 
-    // Timestamped segments
-    w!("=== TIMESTAMPED ===\n\n");
-    for seg in segments {
-        let (sm, ss) = (seg.start as u64 / 60, seg.start as u64 % 60);
-        let (em, es) = (seg.end as u64 / 60, seg.end as u64 % 60);
-        w!("[{sm:02}:{ss:02} -> {em:02}:{es:02}]  {}\n", seg.text);
+/// Post-processed text of `seg`. Turkish-specific fixes would mangle
+/// segments in other languages, so those get only the language-neutral
+/// passes; masking must apply whatever the language.
+fn postprocess_segment(seg: &Segment, config: &postprocess::Config) -> String {
+    if seg.language.as_deref().unwrap_or(DEFAULT_LANGUAGE) == DEFAULT_LANGUAGE {
+        postprocess::process(&seg.text, config)
+    } else {
+        postprocess::process_neutral(&seg.text, config)
     }
+}
+
+/// Progress (percent) at which the measured rate fully replaces the
+/// model's typical realtime factor in the ETA.
+const ETA_WARMUP_PERCENT: f64 = 20.0;
+
+/// Estimated seconds of inference left after `elapsed` seconds at
+/// `percent` complete. Starts from the model's typical realtime factor
+/// and blends toward the measured rate as progress comes in, since the
+/// first few percent are dominated by warm-up.
+fn eta_secs(elapsed: f64, percent: u64, audio_secs: f64, typical_rtf: f64) -> f64 {
+    let expected_total = audio_secs * typical_rtf;
+    if percent == 0 {
+        return (expected_total - elapsed).max(0.0);
+    }
+    let percent = percent.min(100) as f64;
+    let measured_total = elapsed * 100.0 / percent;
+    let weight = (percent / ETA_WARMUP_PERCENT).min(1.0);
+    let total = weight * measured_total + (1.0 - weight) * expected_total;
+    (total - elapsed).max(0.0)
+}
+
+/// Format an ETA as `HH:MM:SS`, matching the elapsed column.
+fn format_eta(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Whether an empty pass should be retried with relaxed settings: only
+/// when nothing survived collection but the audio clearly has signal.
+fn should_retry(segment_count: usize, rms: f32, enabled: bool) -> bool {
+    enabled && segment_count == 0 && rms >= SIGNAL_RMS_THRESHOLD
+}
+
+/// Detect the spoken language and print the top candidates.
+///
+/// Only runs Whisper's language-detection pass over the first 30 seconds
+/// of audio — no transcription and no output file.
+#[tracing::instrument(skip_all, fields(audio = %audio_path.display(), model = model_size))]
+pub fn detect_language(
+    audio_path: &Path,
+    model_size: &str,
+    input_format: Option<&str>,
+    download: &model::DownloadOptions,
+) -> Result<()> {
+    let (model_path, _) = model::resolve_model(model_size, download)?;
+    let (samples, _) = audio::load_audio(audio_path, input_format)?;
+    let ctx = load_context(&model_path)?;
+
+    let mut state = ctx
+        .create_state()
+        .map_err(|e| TranscriptionError::StateCreation(e.to_string()))?;
+
+    let probs = language_probs(&mut state, &samples, inference_threads())?;
+    let top = language::top_candidates(&probs, 3);
+    info!(top = ?top, "Language detection complete");
+    print!("{}", language::format_candidates(&top));
 
     Ok(())
 }
+
+/// Load the Whisper model into a new context.
+fn load_context(model_path: &Path) -> Result<WhisperContext> {
+    let t0 = Instant::now();
+    let model_str = model_path
+        .to_str()
+        .ok_or_else(|| ModelError::InvalidPath(model_path.display().to_string()))?;
+    // GPU is auto-enabled when compiled with vulkan/cuda feature
+    let ctx = WhisperContext::new_with_params(model_str, WhisperContextParameters::default())
+        .map_err(|e| {
+            let note = model::available_models_note(&model::available_models(), model_path);
+            ModelError::LoadFailed(format!("{e}{note}"))
+        })?;
+    let secs = t0.elapsed().as_secs_f64();
+    info!(elapsed_secs = format!("{secs:.1}"), "Whisper model loaded");
+    status!("       Model loaded in {secs:.1}s");
+    Ok(ctx)
+}
+
+/// Number of CPU threads to use for inference.
+fn inference_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
+/// Average probability of the text tokens in `seg`. Special tokens
+/// (timestamps, end-of-text and above) are excluded.
+fn segment_confidence(seg: &whisper_rs::WhisperSegment, token_eot: i32) -> Option<f32> {
+    let probs: Vec<f32> = (0..seg.n_tokens())
+        .filter_map(|i| seg.get_token(i))
+        .filter(|t| t.token_id() < token_eot)
+        .map(|t| t.token_probability())
+        .collect();
+    if probs.is_empty() {
+        return None;
+    }
+    Some(probs.iter().sum::<f32>() / probs.len() as f32)
+}
+
+/// Text and probability of the text tokens in `seg`.
+fn token_probs(seg: &whisper_rs::WhisperSegment, token_eot: i32) -> Vec<TokenProb> {
+    (0..seg.n_tokens())
+        .filter_map(|i| seg.get_token(i))
+        .filter(|t| t.token_id() < token_eot)
+        .filter_map(|t| {
+            Some(TokenProb {
+                text: t.to_str_lossy().ok()?.into_owned(),
+                p: t.token_probability(),
+            })
+        })
+        .collect()
+}
+
+/// Timing of every token in `seg`; tokens from end-of-text up are
+/// special (timestamps and control tokens) rather than speech.
+fn token_spans(seg: &whisper_rs::WhisperSegment, token_eot: i32) -> Vec<segments::TokenSpan> {
+    (0..seg.n_tokens())
+        .filter_map(|i| seg.get_token(i))
+        .map(|t| {
+            let data = t.token_data();
+            segments::TokenSpan {
+                t0: data.t0 as f64 / 100.0,
+                t1: data.t1 as f64 / 100.0,
+                speech: t.token_id() < token_eot,
+            }
+        })
+        .collect()
+}
+
+/// Detection probability for every language Whisper knows, from the
+/// first 30 s of `samples`.
+fn language_probs(
+    state: &mut whisper_rs::WhisperState,
+    samples: &[f32],
+    threads: usize,
+) -> Result<Vec<language::Candidate<'static>>> {
+    state
+        .pcm_to_mel(samples, threads)
+        .map_err(|e| TranscriptionError::InferenceFailed(e.to_string()))?;
+    let (_, probs) = state
+        .lang_detect(0, threads)
+        .map_err(|e| TranscriptionError::InferenceFailed(e.to_string()))?;
+
+    Ok(probs
+        .iter()
+        .enumerate()
+        .filter_map(|(id, &p)| whisper_rs::get_lang_str(id as i32).map(|code| (code, p)))
+        .collect())
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ExitCode;
+
+    #[test]
+    fn dry_run_prepares_without_whisper_context() {
+        let (mut resolved, mut loaded) = (false, false);
+        let prepared = prepare(
+            Path::new("kayit.mp3"),
+            "tiny",
+            |size| {
+                resolved = true;
+                Ok((PathBuf::from(format!("ggml-{size}.bin")), false))
+            },
+            |_| {
+                loaded = true;
+                let info = audio::AudioInfo {
+                    codec: "mp3".to_string(),
+                    sample_rate: 44_100,
+                    channels: 2,
+                };
+                Ok((vec![0.0; 24_000], info))
+            },
+        )
+        .unwrap();
+
+        assert!(resolved && loaded);
+        let summary = prepared.summary();
+        assert!(summary.contains("ggml-tiny.bin (cached/downloaded)"));
+        assert!(summary.contains("Format: mp3, 44100 Hz, 2 ch"));
+        assert!(summary.contains("Duration: 1.5s (24000 samples at 16 kHz)"));
+    }
+
+    #[test]
+    fn dry_run_surfaces_audio_errors() {
+        let err = prepare(
+            Path::new("missing.mp3"),
+            "tiny",
+            |_| Ok((PathBuf::from("ggml-tiny.bin"), false)),
+            |_| Err(crate::errors::AudioError::EmptyAudio.into()),
+        )
+        .err()
+        .expect("audio error must propagate");
+        assert_eq!(ExitCode::from_error(&err), ExitCode::AUDIO_VALIDATION);
+    }
+
+    #[test]
+    fn retry_only_when_signal_but_no_segments() {
+        assert!(should_retry(0, 0.1, true));
+        assert!(!should_retry(3, 0.1, true), "segments found");
+        assert!(!should_retry(0, 0.001, true), "near-silent audio");
+        assert!(!should_retry(0, 0.1, false), "--no-retry");
+    }
+
+    #[test]
+    fn max_chars_enables_token_timestamps() {
+        let settings = DecodeSettings::RELAXED.with_max_chars(Some(42));
+        assert_eq!(settings.max_len(), Some(42));
+        assert!(settings.token_timestamps());
+        assert_eq!(settings.beam_size, None, "other settings kept");
+
+        let settings = DecodeSettings::RELAXED;
+        assert_eq!(settings.max_len(), None);
+        assert!(!settings.token_timestamps());
+
+        let settings = DecodeSettings::RELAXED.with_tightening(true);
+        assert_eq!(settings.max_len(), None);
+        assert!(settings.token_timestamps(), "needed for tightening");
+    }
+
+    #[test]
+    fn eta_starts_from_typical_rate_and_follows_progress() {
+        // 10 min of audio at a typical 0.5x: 300 s expected before any progress
+        assert_eq!(eta_secs(0.0, 0, 600.0, 0.5), 300.0);
+        assert_eq!(eta_secs(400.0, 0, 600.0, 0.5), 0.0, "never negative");
+
+        // Past the warm-up only the measured rate counts: 25% in 100 s
+        assert_eq!(eta_secs(100.0, 25, 600.0, 0.5), 300.0);
+        assert_eq!(eta_secs(100.0, 50, 600.0, 0.5), 100.0);
+        assert_eq!(eta_secs(100.0, 100, 600.0, 0.5), 0.0);
+
+        // Halfway through the warm-up: average of 200 s and 300 s totals
+        assert_eq!(eta_secs(20.0, 10, 600.0, 0.5), 230.0);
+    }
+
+    #[test]
+    fn eta_formatted_like_elapsed() {
+        assert_eq!(format_eta(0.0), "00:00:00");
+        assert_eq!(format_eta(125.4), "00:02:05");
+        assert_eq!(format_eta(3725.0), "01:02:05");
+    }
+
+    #[test]
+    fn suppression_defaults_and_overrides() {
+        let settings = DecodeSettings::first_pass(&profile::for_model("medium"));
+        assert_eq!(
+            (settings.suppress_blank, settings.suppress_nst),
+            (true, false)
+        );
+
+        let settings = DecodeSettings::RELAXED.with_suppression(false, true);
+        assert_eq!(
+            (settings.suppress_blank, settings.suppress_nst),
+            (false, true)
+        );
+        assert_eq!(settings.beam_size, None, "other settings kept");
+    }
+
+    #[test]
+    fn chunk_progress_fills_the_bar_once() {
+        let total = 75 * 16_000;
+        let ranges = chunking::chunk_ranges(total, 30 * 16_000);
+        let spans: Vec<_> = ranges.iter().map(|r| progress_span(r, total)).collect();
+        assert_eq!(spans, vec![0..40, 40..80, 80..100]);
+        assert_eq!(progress_span(&(0..0), 0), 0..0);
+    }
+
+    #[test]
+    fn other_languages_still_masked() {
+        let config = postprocess::Config {
+            mask_words: vec!["acme".to_string()],
+            ..Default::default()
+        };
+        let seg = |language: &str| Segment {
+            text: "Acme hired Peter Dubek.".to_string(),
+            language: Some(language.to_string()),
+            ..Segment::default()
+        };
+        assert_eq!(
+            postprocess_segment(&seg("en"), &config),
+            "**** hired Peter Dubek."
+        );
+        assert_eq!(
+            postprocess_segment(&seg("tr"), &config),
+            "**** hired Peter Drucker."
+        );
+    }
+
+    #[test]
+    fn first_pass_uses_model_profile() {
+        let profile = profile::resolve("large-v3", &profile::Overrides::default());
+        let settings = DecodeSettings::first_pass(&profile);
+        assert_eq!(settings.beam_size, Some(3));
+        assert_eq!(settings.no_speech_thold, 0.5);
+        assert_eq!(settings.temperature_inc, 0.4);
+
+        let overrides = profile::Overrides {
+            beam_size: Some(7),
+            ..Default::default()
+        };
+        let settings = DecodeSettings::first_pass(&profile::resolve("large-v3", &overrides));
+        assert_eq!(settings.beam_size, Some(7));
+        assert_eq!(settings.no_speech_thold, 0.5, "unset values from profile");
+    }
+}
@@ -0,0 +1,288 @@
+//! Energy-based voice activity detection for `--vad`.
+//!
+//! The audio is cut into 30 ms frames and each frame is classified as
+//! speech when its RMS level is above a threshold. Runs of speech shorter
+//! than the minimum are dropped, short pauses are kept, and the remaining
+//! regions are concatenated for inference with a short silence at each
+//! join. A [`TimeMap`] maps timestamps in the concatenated audio back onto
+//! the original timeline.
+
+use std::ops::Range;
+
+use crate::audio;
+use crate::transcribe::Segment;
+
+/// Sample rate of decoded audio.
+const SAMPLE_RATE: usize = 16_000;
+
+/// Analysis frame length (30 ms).
+const FRAME: usize = SAMPLE_RATE * 30 / 1000;
+
+/// Audio kept on each side of a speech region so word edges aren't cut.
+const PADDING_MS: usize = 200;
+
+/// Pauses shorter than this stay inside a speech region.
+const MIN_SILENCE_MS: usize = 500;
+
+/// Silence inserted between regions so Whisper ends a segment at each join.
+const JOIN_SILENCE: usize = SAMPLE_RATE / 2;
+
+/// Default speech threshold in dBFS.
+pub const DEFAULT_THRESHOLD_DB: f32 = -40.0;
+
+/// Default minimum length of a speech run in milliseconds.
+pub const DEFAULT_MIN_SPEECH_MS: u32 = 250;
+
+/// Detection settings.
+#[derive(Clone, Debug)]
+pub struct VadConfig {
+    /// Frame RMS level (dBFS) at or above which a frame counts as speech
+    pub threshold_db: f32,
+    /// Speech runs shorter than this (ms) are treated as noise
+    pub min_speech_ms: u32,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            threshold_db: DEFAULT_THRESHOLD_DB,
+            min_speech_ms: DEFAULT_MIN_SPEECH_MS,
+        }
+    }
+}
+
+/// Classify each `FRAME`-sample frame as speech (`true`) or not.
+pub fn classify_frames(samples: &[f32], threshold_db: f32) -> Vec<bool> {
+    let threshold = 10f32.powf(threshold_db / 20.0);
+    samples
+        .chunks(FRAME)
+        .map(|frame| audio::rms(frame) >= threshold)
+        .collect()
+}
+
+/// Sample ranges that contain speech: runs of speech frames at least
+/// `min_speech_ms` long, padded, with short pauses between them merged.
+pub fn speech_regions(samples: &[f32], config: &VadConfig) -> Vec<Range<usize>> {
+    let frames = classify_frames(samples, config.threshold_db);
+    let min_frames = (config.min_speech_ms as usize * SAMPLE_RATE / 1000).div_ceil(FRAME);
+    let pad = PADDING_MS * SAMPLE_RATE / 1000;
+    let min_gap = MIN_SILENCE_MS * SAMPLE_RATE / 1000;
+
+    let mut regions: Vec<Range<usize>> = Vec::new();
+    let mut i = 0;
+    while i < frames.len() {
+        if !frames[i] {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < frames.len() && frames[i] {
+            i += 1;
+        }
+        if i - start < min_frames.max(1) {
+            continue;
+        }
+        let range = (start * FRAME).saturating_sub(pad)..(i * FRAME + pad).min(samples.len());
+        match regions.last_mut() {
+            Some(last) if range.start <= last.end + min_gap => last.end = range.end,
+            _ => regions.push(range),
+        }
+    }
+    regions
+}
+
+/// Placement of the speech regions inside the concatenated audio, which
+/// has `JOIN_SILENCE` samples of silence between neighbouring regions.
+#[derive(Debug, PartialEq)]
+pub struct TimeMap {
+    /// `(offset in concatenated audio, original range)` per region
+    regions: Vec<(usize, Range<usize>)>,
+}
+
+impl TimeMap {
+    pub fn new(regions: &[Range<usize>]) -> Self {
+        let mut offset = 0;
+        let regions = regions
+            .iter()
+            .map(|r| {
+                let entry = (offset, r.clone());
+                offset += r.len() + JOIN_SILENCE;
+                entry
+            })
+            .collect();
+        Self { regions }
+    }
+
+    /// Concatenate the speech regions of `samples`, separated by silence.
+    pub fn gather(&self, samples: &[f32]) -> Vec<f32> {
+        let mut gathered = Vec::new();
+        for (i, (_, r)) in self.regions.iter().enumerate() {
+            if i > 0 {
+                gathered.resize(gathered.len() + JOIN_SILENCE, 0.0);
+            }
+            gathered.extend_from_slice(&samples[r.clone()]);
+        }
+        gathered
+    }
+
+    /// Seconds of audio kept for inference.
+    pub fn kept_secs(&self) -> f64 {
+        let kept: usize = self.regions.iter().map(|(_, r)| r.len()).sum();
+        kept as f64 / SAMPLE_RATE as f64
+    }
+
+    /// Index of the region `secs` in the concatenated audio belongs to. A
+    /// time in the silence at a join belongs to the earlier region when it
+    /// ends a segment (`is_end`) and to the later one when it starts one.
+    fn region_at(&self, secs: f64, is_end: bool) -> Option<usize> {
+        let last = self.regions.len().checked_sub(1)?;
+        let pos = secs * SAMPLE_RATE as f64;
+        let found = if is_end {
+            self.regions
+                .iter()
+                .rposition(|(offset, _)| (*offset as f64) < pos)
+        } else {
+            self.regions
+                .iter()
+                .position(|(offset, r)| pos < (offset + r.len()) as f64)
+        };
+        Some(found.unwrap_or(if is_end { 0 } else { last }))
+    }
+
+    /// Map `secs` in the concatenated audio to the original timeline,
+    /// clamped to the edges of the region it belongs to.
+    pub fn to_original(&self, secs: f64, is_end: bool) -> f64 {
+        let Some(index) = self.region_at(secs, is_end) else {
+            return secs;
+        };
+        let (offset, r) = &self.regions[index];
+        let within = (secs * SAMPLE_RATE as f64 - *offset as f64).clamp(0.0, r.len() as f64);
+        (r.start as f64 + within) / SAMPLE_RATE as f64
+    }
+
+    /// Move segment timestamps back onto the original timeline. A segment
+    /// Whisper ran across a join would cover the skipped audio, so it is
+    /// cut back to the side of the join that holds most of it.
+    pub fn remap(&self, segments: &mut [Segment]) {
+        let secs = |sample: usize| sample as f64 / SAMPLE_RATE as f64;
+        for seg in segments {
+            let mut start = self.to_original(seg.start, false);
+            let mut end = self.to_original(seg.end, true);
+            let first = self.region_at(seg.start, false);
+            let last = self.region_at(seg.end, true);
+            if let (Some(first), Some(last)) = (first, last) {
+                if last > first {
+                    let first_end = secs(self.regions[first].1.end);
+                    let last_start = secs(self.regions[last].1.start);
+                    if first_end - start >= end - last_start {
+                        end = first_end;
+                    } else {
+                        start = last_start;
+                    }
+                }
+            }
+            seg.start = start;
+            seg.end = end;
+        }
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `secs` seconds of a 0.3-amplitude square wave (about -10 dBFS).
+    fn loud(secs: f64) -> Vec<f32> {
+        (0..(secs * SAMPLE_RATE as f64) as usize)
+            .map(|i| if i % 40 < 20 { 0.3 } else { -0.3 })
+            .collect()
+    }
+
+    fn silence(secs: f64) -> Vec<f32> {
+        vec![0.0; (secs * SAMPLE_RATE as f64) as usize]
+    }
+
+    #[test]
+    fn frames_classified_by_energy() {
+        let samples = [silence(0.09), loud(0.09), vec![0.001; FRAME * 2]].concat();
+        assert_eq!(
+            classify_frames(&samples, -40.0),
+            vec![false, false, false, true, true, true, false, false]
+        );
+        // A lower threshold lets the -60 dBFS hiss through
+        assert!(classify_frames(&samples, -70.0)[6]);
+    }
+
+    #[test]
+    fn short_bursts_dropped_and_short_pauses_kept() {
+        let config = VadConfig::default();
+        // 0.06 s click, 2 s silence, 1 s speech, 0.3 s pause, 1 s speech
+        let samples = [
+            loud(0.06),
+            silence(2.0),
+            loud(1.0),
+            silence(0.3),
+            loud(1.0),
+            silence(2.0),
+        ]
+        .concat();
+        let regions = speech_regions(&samples, &config);
+        assert_eq!(regions.len(), 1, "{regions:?}");
+        let secs = |s: usize| s as f64 / SAMPLE_RATE as f64;
+        assert!((secs(regions[0].start) - 1.86).abs() < 0.05);
+        assert!((secs(regions[0].end) - 4.56).abs() < 0.05);
+    }
+
+    fn seg(start: f64, end: f64) -> Segment {
+        Segment {
+            start,
+            end,
+            text: "konuşma".to_string(),
+            ..Segment::default()
+        }
+    }
+
+    #[test]
+    fn timestamps_remapped_across_skipped_region() {
+        // Speech at 0–2 s and 10–13 s; 2–10 s skipped, 0.5 s of silence
+        // at the join
+        let map = TimeMap::new(&[0..2 * SAMPLE_RATE, 10 * SAMPLE_RATE..13 * SAMPLE_RATE]);
+        assert_eq!(map.kept_secs(), 5.0);
+        assert_eq!(map.to_original(1.5, false), 1.5);
+        assert_eq!(map.to_original(3.0, false), 10.5);
+        // The join: an ending segment stays in the first region, a
+        // starting one moves past the gap
+        assert_eq!(map.to_original(2.0, true), 2.0);
+        assert_eq!(map.to_original(2.0, false), 10.0);
+        assert_eq!(map.to_original(2.25, true), 2.0);
+        assert_eq!(map.to_original(2.25, false), 10.0);
+        assert_eq!(map.to_original(6.5, true), 13.0, "clamped to the end");
+
+        let mut segments = vec![seg(3.0, 4.5)];
+        map.remap(&mut segments);
+        assert_eq!((segments[0].start, segments[0].end), (10.5, 12.0));
+    }
+
+    #[test]
+    fn segment_across_join_kept_on_one_side() {
+        let map = TimeMap::new(&[0..2 * SAMPLE_RATE, 10 * SAMPLE_RATE..13 * SAMPLE_RATE]);
+        // Mostly before the join, then mostly after it
+        let mut segments = vec![seg(1.0, 2.75), seg(1.8, 4.0)];
+        map.remap(&mut segments);
+        assert_eq!((segments[0].start, segments[0].end), (1.0, 2.0));
+        assert_eq!((segments[1].start, segments[1].end), (10.0, 11.5));
+    }
+
+    #[test]
+    fn gather_separates_regions_with_silence() {
+        let samples: Vec<f32> = (1..=10).map(|i| i as f32).collect();
+        let map = TimeMap::new(&[1..3, 6..8]);
+        let gathered = map.gather(&samples);
+        assert_eq!(gathered.len(), 4 + JOIN_SILENCE);
+        assert_eq!(gathered[..2], [2.0, 3.0]);
+        assert!(gathered[2..2 + JOIN_SILENCE].iter().all(|&s| s == 0.0));
+        assert_eq!(gathered[2 + JOIN_SILENCE..], [7.0, 8.0]);
+    }
+}